@@ -1,8 +1,12 @@
 use crate::{
     config::{CargoCompeteConfig, CargoCompeteConfigNew},
     oj_api,
+    project::{MetadataExt as _, PackageExt as _},
     shell::{ColorChoice, Shell},
-    web::input_template::generate_template,
+    web::{
+        input_template::{generate_template, BinTarget, TemplateOptions},
+        judge::{save_tasks_if_missing, AtCoder, Judge, Yukicoder},
+    },
 };
 use anyhow::{bail, Context as _};
 use camino::{Utf8Path, Utf8PathBuf};
@@ -10,7 +14,7 @@ use heck::KebabCase as _;
 use itertools::Itertools as _;
 use liquid::object;
 use snowchains_core::web::{PlatformKind, ProblemsInContest, YukicoderRetrieveTestCasesTargets};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use structopt::StructOpt;
 use strum::VariantNames as _;
 use url::Url;
@@ -29,6 +33,14 @@ pub struct OptCompeteNew {
     #[structopt(long, value_name("INDEX"))]
     pub problems: Option<Vec<String>>,
 
+    /// Only replace the `input!` of the generated files with the one guessed from the statement
+    #[structopt(long)]
+    pub update_input_only: bool,
+
+    /// Generate the `input!` templates only for these tasks (e.g. `D-F Ex`)
+    #[structopt(long, value_name("LETTERS"))]
+    pub letters: Option<Vec<String>>,
+
     /// Path to `compete.toml`
     #[structopt(long, value_name("PATH"))]
     pub config: Option<Utf8PathBuf>,
@@ -51,6 +63,8 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
         full,
         open,
         problems,
+        update_input_only,
+        letters,
         config,
         color,
         contest,
@@ -79,7 +93,13 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
             let contest = contest.with_context(|| "`contest` is required for AtCoder")?;
             let problems = problems.map(|ps| ps.into_iter().collect());
 
-            crate::web::cookie_atcoder_py::update_atcoder_cookie_best_effort(&cookies_path, shell);
+            if let Some(target) = AtCoder.cookie_target() {
+                crate::web::cookie_atcoder_py::update_cookie_best_effort(
+                    target,
+                    &cookies_path,
+                    shell,
+                );
+            }
 
             let outcome = crate::web::retrieve_testcases::dl_from_atcoder(
                 ProblemsInContest::Indexes { contest, problems },
@@ -107,26 +127,36 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
                 shell,
             )?;
 
-            if let Some(contest) = group.contest() {
-                crate::web::tasks_print_html::save_atcoder_tasks_print_if_missing(
-                    contest,
+            let generated = if let Some(contest) = group.contest() {
+                save_tasks_if_missing(
+                    &AtCoder,
+                    Some(contest),
+                    &problems,
                     &manifest_dir,
                     &cookies_path,
                     shell,
                 )?;
-                match generate_template(&manifest_dir, shell)? {
-                    None => {}
-                    Some(srcs) => {
-                        for (src_path, content) in srcs {
-                            crate::fs::write(&src_path, content)?;
-                            shell.status("Wrote", src_path)?;
-                        }
-                    }
+                let template_options = TemplateOptions {
+                    contest: Some(contest.to_owned()),
+                    task_slugs: problems
+                        .iter()
+                        .filter_map(|(&index, url)| {
+                            let slug = url.path_segments()?.next_back()?;
+                            Some((index.to_owned(), slug.to_owned()))
+                        })
+                        .collect(),
+                    cookies_path: cookies_path.clone(),
+                    update_input_only,
+                    letters,
+                    ..cargo_compete_config.input_template.clone()
                 };
-            }
+                write_templates(&manifest_dir, &AtCoder, &template_options, shell)?
+            } else {
+                HashMap::new()
+            };
 
             let file_paths = itertools::zip_eq(
-                src_paths,
+                generated_paths(&problems, src_paths, generated),
                 crate::web::retrieve_testcases::save_test_cases(
                     &cargo_compete_dir,
                     &manifest_dir,
@@ -250,8 +280,23 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
                 shell,
             )?;
 
+            save_tasks_if_missing(
+                &Yukicoder,
+                None,
+                &problems,
+                &manifest_dir,
+                &cookies_path,
+                shell,
+            )?;
+            let template_options = TemplateOptions {
+                update_input_only,
+                letters,
+                ..cargo_compete_config.input_template.clone()
+            };
+            let generated = write_templates(&manifest_dir, &Yukicoder, &template_options, shell)?;
+
             let file_paths = itertools::zip_eq(
-                src_paths,
+                generated_paths(&problems, src_paths, generated),
                 crate::web::retrieve_testcases::save_test_cases(
                     &cargo_compete_dir,
                     &manifest_dir,
@@ -355,6 +400,203 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// `src_paths` of `create_new_package`, with the ones of the tasks in `generated` replaced by the
+/// files written for them, so that those are what get opened and tested.
+fn generated_paths(
+    problems: &BTreeMap<&str, &Url>,
+    src_paths: Vec<Utf8PathBuf>,
+    mut generated: HashMap<String, Utf8PathBuf>,
+) -> Vec<Utf8PathBuf> {
+    itertools::zip_eq(problems.keys(), src_paths)
+        .map(|(index, src_path)| generated.remove(&index.to_kebab_case()).unwrap_or(src_path))
+        .collect()
+}
+
+/// Returns the written files by the kebab-cased letters of their tasks.
+fn write_templates(
+    manifest_dir: &Utf8Path,
+    judge: &dyn Judge,
+    options: &TemplateOptions,
+    shell: &mut Shell,
+) -> anyhow::Result<HashMap<String, Utf8PathBuf>> {
+    let Some(generated) = generate_template(manifest_dir, judge, options, None, shell)? else {
+        return Ok(HashMap::new());
+    };
+    let mut src_paths = vec![];
+    let mut written = HashMap::new();
+    for src_path in generated.order {
+        let source = &generated.sources[&src_path];
+        crate::fs::write(&src_path, &source.content)?;
+        let mut notes = vec![];
+        if let Some(points) = source.points {
+            notes.push(format!("{points} points"));
+        }
+        if !source.applied_options.is_empty() {
+            notes.push(format!("with {}", source.applied_options.join(", ")));
+        }
+        if notes.is_empty() {
+            shell.status("Wrote", &src_path)?;
+        } else {
+            shell.status("Wrote", format!("{src_path} ({})", notes.join("; ")))?;
+        }
+        for diagnostic in &source.diagnostics {
+            shell.status(
+                "Note",
+                format!("{src_path}:{}: {}", diagnostic.line, diagnostic.message),
+            )?;
+        }
+        written.insert(source.letter.to_kebab_case(), src_path.clone());
+        src_paths.push(src_path);
+    }
+    add_missing_dependencies(
+        manifest_dir,
+        &generated.dependencies,
+        options.add_dependencies,
+        shell,
+    )?;
+    if !generated.bin_targets.is_empty() {
+        set_bin_targets(manifest_dir, &generated.bin_targets, shell)?;
+    }
+    if options.check {
+        src_paths.sort();
+        check_generated(&src_paths, shell)?;
+    }
+    Ok(written)
+}
+
+/// Runs `cargo check` for the `bin`s of `paths`, and warns with the first error of the ones that
+/// fail.
+pub(crate) fn check_generated(paths: &[Utf8PathBuf], shell: &mut Shell) -> anyhow::Result<()> {
+    for path in paths {
+        let dir = path
+            .parent()
+            .with_context(|| format!("`{path}` has no parent"))?;
+        let manifest_path = crate::project::locate_project(dir)?;
+        let metadata = crate::project::cargo_metadata_no_deps(&manifest_path, dir)?;
+        let Some(bin) = metadata
+            .all_members()
+            .into_iter()
+            .find_map(|p| p.bin_target_by_src_path(path).ok())
+        else {
+            shell.warn(format!(
+                "`{path}` is not a `bin` target. Skipping the check"
+            ))?;
+            continue;
+        };
+
+        let (status, stderr) = crate::process::process(crate::process::cargo_exe()?)
+            .args(&[
+                "check",
+                "--message-format",
+                "short",
+                "--bin",
+                bin.name.as_str(),
+            ])
+            .arg("--manifest-path")
+            .arg(&manifest_path)
+            .cwd(dir)
+            .status_with_stderr()?;
+        if status.success() {
+            shell.status("Checked", path)?;
+        } else {
+            let first_error = stderr
+                .lines()
+                .find(|l| l.contains("error") && !l.starts_with("error: could not compile"))
+                .unwrap_or("(no error message)");
+            shell.warn(format!(
+                "the template `{path}` does not compile: {first_error}"
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// Adds the `dependencies` missing in `[dependencies]` if `write`, and otherwise only shows them.
+fn add_missing_dependencies(
+    manifest_dir: &Utf8Path,
+    dependencies: &BTreeMap<&str, &str>,
+    write: bool,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let manifest_path = manifest_dir.join("Cargo.toml");
+
+    let mut manifest = crate::fs::read_to_string(&manifest_path)?
+        .parse::<toml_edit::Document>()
+        .with_context(|| format!("could not parse the TOML file at `{manifest_path}`"))?;
+
+    if manifest.get("dependencies").is_none() {
+        manifest["dependencies"] = toml_edit::table();
+    }
+    let mut dirty = false;
+    for (&name, &version) in dependencies {
+        if manifest["dependencies"].get(name).is_some() {
+            continue;
+        }
+        if write {
+            manifest["dependencies"][name] = toml_edit::value(version);
+            dirty = true;
+        } else {
+            shell.status(
+                "Note",
+                format!(
+                    "the templates use `{name}`. Add `{name} = \"{version}\"` to \
+                     `[dependencies]` of `{manifest_path}`",
+                ),
+            )?;
+        }
+    }
+    if dirty {
+        crate::fs::write(&manifest_path, manifest.to_string())?;
+        shell.status("Wrote", &manifest_path)?;
+    }
+    Ok(())
+}
+
+/// Points the `[[bin]]`s of `targets` at the generated files, adding the ones that are missing.
+fn set_bin_targets(
+    manifest_dir: &Utf8Path,
+    targets: &[BinTarget],
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let manifest_path = manifest_dir.join("Cargo.toml");
+
+    let mut manifest = crate::fs::read_to_string(&manifest_path)?
+        .parse::<toml_edit::Document>()
+        .with_context(|| format!("could not parse the TOML file at `{manifest_path}`"))?;
+
+    if manifest.get("bin").is_none() {
+        manifest["bin"] = toml_edit::Item::ArrayOfTables(toml_edit::ArrayOfTables::new());
+    }
+    let bins = manifest["bin"]
+        .as_array_of_tables_mut()
+        .with_context(|| format!("`bin` in `{manifest_path}` is not an array of tables"))?;
+    let mut dirty = false;
+    for target in targets {
+        let existing = bins
+            .iter_mut()
+            .find(|bin| bin["name"].as_str() == Some(&target.name));
+        match existing {
+            Some(bin) if bin["path"].as_str() == Some(&target.path) => {}
+            Some(bin) => {
+                bin["path"] = toml_edit::value(&*target.path);
+                dirty = true;
+            }
+            None => {
+                let mut bin = toml_edit::Table::new();
+                bin["name"] = toml_edit::value(&*target.name);
+                bin["path"] = toml_edit::value(&*target.path);
+                bins.push(bin);
+                dirty = true;
+            }
+        }
+    }
+    if dirty {
+        crate::fs::write(&manifest_path, manifest.to_string())?;
+        shell.status("Wrote", &manifest_path)?;
+    }
+    Ok(())
+}
+
 fn urls(outcome: &[crate::web::retrieve_testcases::Problem<impl Sized>]) -> Vec<Url> {
     outcome.iter().map(|p| p.url.clone()).collect()
 }
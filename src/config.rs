@@ -1,4 +1,4 @@
-use crate::{project::PackageExt as _, shell::Shell};
+use crate::{project::PackageExt as _, shell::Shell, web::input_template::TemplateOptions};
 use anyhow::{bail, Context as _};
 use camino::{Utf8Path, Utf8PathBuf};
 use cargo_metadata as cm;
@@ -132,6 +132,8 @@ pub(crate) struct CargoCompeteConfig {
     pub(crate) test: CargoCompeteConfigTest,
     #[serde(default)]
     pub(crate) submit: CargoCompeteConfigSubmit,
+    #[serde(default)]
+    pub(crate) input_template: TemplateOptions,
 }
 
 impl CargoCompeteConfig {
@@ -1,6 +1,9 @@
 use anyhow::Context as _;
 use serde::{de::DeserializeOwned, Serialize};
-use std::path::{Path, PathBuf};
+use std::{
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+};
 
 pub(crate) fn read_to_string(path: impl AsRef<Path>) -> anyhow::Result<String> {
     let path = path.as_ref();
@@ -24,6 +27,27 @@ pub(crate) fn write(path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> anyhow
     std::fs::write(path, content).with_context(|| format!("could not write `{}`", path.display()))
 }
 
+/// Writes `content` to a temporary file in the directory of `path`, then renames it to `path`. An
+/// interrupted write leaves `path` as it was instead of truncated.
+pub(crate) fn write_atomic(
+    path: impl AsRef<Path>,
+    content: impl AsRef<[u8]>,
+) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    (|| -> io::Result<()> {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let mut file = tempfile::NamedTempFile::new_in(dir)?;
+        file.write_all(content.as_ref())?;
+        file.as_file().sync_all()?;
+        file.persist(path).map_err(|err| err.error)?;
+        Ok(())
+    })()
+    .with_context(|| format!("could not write `{}`", path.display()))
+}
+
 pub(crate) fn write_json(path: impl AsRef<Path>, content: impl Serialize) -> anyhow::Result<()> {
     write(path, serde_json::to_string(&content)?)
 }
@@ -46,3 +70,22 @@ pub(crate) fn read_dir(path: impl AsRef<Path>) -> anyhow::Result<Vec<PathBuf>> {
         .collect::<Result<_, _>>()
         .with_context(|| format!("could not list files in `{}`", path.display()))
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn write_atomic() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-fs-")
+            .tempdir()?;
+        let path = tempdir.path().join("task.html");
+        super::write_atomic(&path, "first")?;
+        super::write_atomic(&path, "second")?;
+        assert_eq!("second", std::fs::read_to_string(&path)?);
+        // No temporary file is left behind.
+        assert_eq!(1, std::fs::read_dir(tempdir.path())?.count());
+        Ok(())
+    }
+}
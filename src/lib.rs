@@ -4,6 +4,7 @@
 mod commands;
 mod config;
 mod fs;
+pub mod naming;
 mod oj_api;
 mod open;
 mod process;
@@ -12,6 +13,16 @@ pub mod shell;
 mod testing;
 mod web;
 
+pub use crate::{
+    shell::Reporter,
+    web::{
+        input_template::{
+            generate_from_format, generate_template_from_html, generate_templates, NameFn,
+        },
+        tasks_print_html::{save_many_tasks_print, SaveOutcome},
+    },
+};
+
 use crate::{
     commands::{
         add::OptCompeteAdd, init::OptCompeteInit, login::OptCompeteLogin,
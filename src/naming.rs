@@ -0,0 +1,104 @@
+//! Turning the symbols in problem statements into Rust identifiers and expressions.
+
+use regex::Regex;
+
+/// Converts `s` into a snake_case identifier.
+///
+/// ASCII letters are lowercased and every run of other characters becomes a single `_`.
+/// Leading and trailing underscores are dropped, so `"Q_i"` becomes `"q_i"` and `"(A, B)"`
+/// becomes `"a_b"`.
+pub fn snake(s: &str) -> String {
+    let mut out = String::new();
+    let mut prev_is_underscore = false;
+    for ch in s.chars() {
+        let c = if ch.is_ascii_alphanumeric() { ch } else { '_' };
+        if c == '_' {
+            if !prev_is_underscore {
+                out.push('_');
+            }
+            prev_is_underscore = true;
+        } else {
+            out.push(c.to_ascii_lowercase());
+            prev_is_underscore = false;
+        }
+    }
+    out.trim_matches('_').to_string()
+}
+
+/// Converts a length written in a statement into a Rust expression.
+///
+/// - `H \times W`, `H \cdot W`, `H*W` and `HW` become `h*w`.
+/// - `N-1` becomes `n-1`.
+/// - `5N` becomes `5*n`.
+/// - Other names made of letters go through [`snake`].
+///
+/// Anything else is returned with whitespace and backslashes removed.
+pub fn sym_expr(s: &str) -> String {
+    // Convert common AtCoder latex-ish symbols to a Rust-ish expression: N-1, 5N, etc.
+    let mut t = s.trim().replace(' ', "");
+    t = t.replace('\\', "");
+    // H \times W, H*W
+    let t = t.replace("times", "*").replace("cdot", "*");
+    if t.contains('*') {
+        return t.split('*').map(sym_expr).collect::<Vec<_>>().join("*");
+    }
+    // HW
+    if (2..=3).contains(&t.len()) && t.chars().all(|c| c.is_ascii_uppercase()) {
+        return t
+            .chars()
+            .map(|c| c.to_ascii_lowercase().to_string())
+            .collect::<Vec<_>>()
+            .join("*");
+    }
+    if let Some((a, b)) = t.split_once('-') {
+        if b.chars().all(|c| c.is_ascii_digit()) {
+            return format!("{}-{}", snake(a), b);
+        }
+    }
+    // 5N form
+    let coef_re = Regex::new(r"^(\d+)([A-Za-z]+)$").unwrap();
+    if let Some(cap) = coef_re.captures(&t) {
+        return format!("{}*{}", &cap[1], snake(&cap[2]));
+    }
+    if t.chars().all(|c| c.is_ascii_alphabetic()) {
+        return snake(&t);
+    }
+    t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{snake, sym_expr};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn snake_collapses_underscores() {
+        assert_eq!("q_i", snake("Q_i"));
+        assert_eq!("a_b", snake("(A, B)"));
+        assert_eq!("x_y", snake("__X--__Y__"));
+        assert_eq!("", snake("_-_"));
+    }
+
+    #[test]
+    fn sym_expr_coefficients() {
+        assert_eq!("5*n", sym_expr("5N"));
+        assert_eq!("2*n", sym_expr("2N"));
+        assert_eq!("12*k", sym_expr("12K"));
+    }
+
+    #[test]
+    fn sym_expr_subtractions() {
+        assert_eq!("n-1", sym_expr("N-1"));
+        assert_eq!("n-1", sym_expr("N - 1"));
+        assert_eq!("N-M", sym_expr("N-M"));
+    }
+
+    #[test]
+    fn sym_expr_products() {
+        assert_eq!("h*w", sym_expr(r"H \times W"));
+        assert_eq!("h*w", sym_expr(r"H \cdot W"));
+        assert_eq!("h*w", sym_expr("HW"));
+        assert_eq!("h*w", sym_expr("H*W"));
+        assert_eq!("n", sym_expr("N"));
+    }
+}
@@ -69,12 +69,24 @@ impl ProcessBuilder<Present> {
     }
 
     pub(crate) fn status(&self) -> anyhow::Result<ExitStatus> {
-        self.spawn(Stdio::inherit())?.wait().map_err(Into::into)
+        self.spawn(Stdio::inherit(), Stdio::inherit())?
+            .wait()
+            .map_err(Into::into)
+    }
+
+    /// Returns the status together with the stderr, which is not shown.
+    pub(crate) fn status_with_stderr(&self) -> anyhow::Result<(ExitStatus, String)> {
+        let std::process::Output { status, stderr, .. } = self
+            .spawn(Stdio::inherit(), Stdio::piped())?
+            .wait_with_output()?;
+        let stderr = String::from_utf8(stderr).with_context(|| "non UTF-8 output")?;
+        Ok((status, stderr))
     }
 
     fn read(&self) -> anyhow::Result<String> {
-        let std::process::Output { status, stdout, .. } =
-            self.spawn(Stdio::piped())?.wait_with_output()?;
+        let std::process::Output { status, stdout, .. } = self
+            .spawn(Stdio::piped(), Stdio::inherit())?
+            .wait_with_output()?;
         if !status.success() {
             bail!("{} didn't exit successfully: {}", self, status);
         }
@@ -86,7 +98,7 @@ impl ProcessBuilder<Present> {
         self.read()
     }
 
-    fn spawn(&self, stdout: Stdio) -> anyhow::Result<std::process::Child> {
+    fn spawn(&self, stdout: Stdio, stderr: Stdio) -> anyhow::Result<std::process::Child> {
         let mut child = std::process::Command::new(&self.program)
             .args(&self.args)
             .current_dir(&self.cwd)
@@ -96,6 +108,7 @@ impl ProcessBuilder<Present> {
                 Stdio::inherit()
             })
             .stdout(stdout)
+            .stderr(stderr)
             .spawn()?;
 
         if let (Some(mut stdin), Some(pipe_input)) = (child.stdin.take(), self.pipe_input.as_ref())
@@ -136,6 +136,50 @@ impl Shell {
     }
 }
 
+/// Where the download and template functions report to, so that they do not depend on a terminal.
+///
+/// `Shell` prints to stderr. Implement this to show the messages of `generate_templates` in
+/// another way.
+pub trait Reporter {
+    /// Progress such as `Downloading` or `Wrote`, with `status` being the verb.
+    fn report_status(
+        &mut self,
+        status: &dyn fmt::Display,
+        message: &dyn fmt::Display,
+    ) -> io::Result<()>;
+
+    /// Something that did not stop the work, but may leave a part of it undone.
+    fn report_warning(&mut self, message: &dyn fmt::Display) -> io::Result<()>;
+}
+
+impl dyn Reporter + '_ {
+    pub(crate) fn status(
+        &mut self,
+        status: impl fmt::Display,
+        message: impl fmt::Display,
+    ) -> io::Result<()> {
+        self.report_status(&status, &message)
+    }
+
+    pub(crate) fn warn(&mut self, message: impl fmt::Display) -> io::Result<()> {
+        self.report_warning(&message)
+    }
+}
+
+impl Reporter for Shell {
+    fn report_status(
+        &mut self,
+        status: &dyn fmt::Display,
+        message: &dyn fmt::Display,
+    ) -> io::Result<()> {
+        self.status(status, message)
+    }
+
+    fn report_warning(&mut self, message: &dyn fmt::Display) -> io::Result<()> {
+        self.warn(message)
+    }
+}
+
 impl Default for Shell {
     fn default() -> Self {
         Self::new()
@@ -1,8 +1,10 @@
 use crate::shell::Shell;
+use anyhow::Context as _;
 use chrono::{DateTime, Utc};
 use rusqlite::{Connection, OptionalExtension};
 use std::{
     env,
+    ffi::OsString,
     fs,
     path::{Path, PathBuf},
 };
@@ -13,7 +15,54 @@ const FIREFOX_DIRS: &[&str] = &[
     ".var/app/org.mozilla.firefox/.mozilla/firefox",
 ];
 
-pub(crate) fn update_atcoder_cookie_best_effort(cookies_path: &Path, shell: &mut Shell) {
+// Relative to `%APPDATA%`.
+const FIREFOX_DIRS_WINDOWS: &[&str] = &["Mozilla/Firefox/Profiles"];
+
+// Relative to `%LOCALAPPDATA%`. The installer keeps a profile here too, and the Microsoft Store
+// version keeps everything in its package.
+const FIREFOX_DIRS_WINDOWS_LOCAL: &[&str] = &[
+    "Mozilla/Firefox/Profiles",
+    "Packages/Mozilla.Firefox_n80bbvh6b1yt2/LocalCache/Roaming/Mozilla/Firefox/Profiles",
+];
+
+/// A session cookie to copy from the browser.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CookieTarget {
+    pub(crate) domain: &'static str,
+    pub(crate) name: &'static str,
+}
+
+impl CookieTarget {
+    pub(crate) const ATCODER: Self = Self {
+        domain: "atcoder.jp",
+        name: "REVEL_SESSION",
+    };
+
+    /// AtCoder keeps using the shared cookie jar. Other domains get their own file next to it so
+    /// that they don't overwrite each other.
+    pub(crate) fn jar_path(self, cookies_path: &Path) -> PathBuf {
+        if self == Self::ATCODER {
+            return cookies_path.to_owned();
+        }
+        let stem = cookies_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "cookies".to_owned());
+        cookies_path.with_file_name(format!("{stem}.{}.jsonl", self.domain))
+    }
+}
+
+impl Default for CookieTarget {
+    fn default() -> Self {
+        Self::ATCODER
+    }
+}
+
+pub(crate) fn update_cookie_best_effort(
+    target: CookieTarget,
+    cookies_path: &Path,
+    shell: &mut Shell,
+) {
     let browser = env::var("ACCC_BROWSER").unwrap_or_else(|_| "firefox".into());
 
     if browser != "firefox" {
@@ -21,19 +70,38 @@ pub(crate) fn update_atcoder_cookie_best_effort(cookies_path: &Path, shell: &mut
         return;
     }
 
-    match update_from_firefox(cookies_path) {
+    match update_from_firefox(target, &target.jar_path(cookies_path)) {
         Ok(()) => {}
         Err(e) => {
-            let _ = shell.warn(format!("cookie update skipped: {e}"));
+            let _ = shell.warn(format!("cookie update skipped: {e:#}"));
         }
     }
 }
 
-fn update_from_firefox(cookies_path: &Path) -> anyhow::Result<()> {
-    let db = newest_cookie_db().ok_or_else(|| anyhow::anyhow!("no firefox cookies.sqlite found"))?;
+fn update_from_firefox(target: CookieTarget, cookies_path: &Path) -> anyhow::Result<()> {
+    let roots = firefox_roots();
+    let db = newest_cookie_db(&roots).ok_or_else(|| {
+        anyhow::anyhow!(
+            "no firefox cookies.sqlite found under {}",
+            roots
+                .iter()
+                .map(|r| format!("`{}`", r.display()))
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+    })?;
+    read_firefox_cookie_db(&db, target, cookies_path)
+        .with_context(|| format!("failed to read `{}`", db.display()))
+}
+
+fn read_firefox_cookie_db(
+    db: &Path,
+    target: CookieTarget,
+    cookies_path: &Path,
+) -> anyhow::Result<()> {
     let tempdir = tempfile::tempdir()?;
     let tmp_db = tempdir.path().join("cookies.sqlite");
-    fs::copy(&db, &tmp_db)?;
+    fs::copy(db, &tmp_db)?;
     let wal = db.with_file_name(format!("{}-wal", db.file_name().unwrap().to_string_lossy()));
     let shm = db.with_file_name(format!("{}-shm", db.file_name().unwrap().to_string_lossy()));
     if wal.exists() {
@@ -47,15 +115,15 @@ fn update_from_firefox(cookies_path: &Path) -> anyhow::Result<()> {
     let row: Option<(String, String, String, String, Option<i64>)> = conn
         .query_row(
             "SELECT host, name, value, path, expiry FROM moz_cookies \
-             WHERE host LIKE '%atcoder.jp%' AND name='REVEL_SESSION' \
+             WHERE host LIKE ?1 AND name=?2 \
              ORDER BY lastAccessed DESC LIMIT 1",
-            [],
+            [format!("%{}%", target.domain), target.name.to_owned()],
             |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?)),
         )
         .optional()?;
 
     let (host, name, value, path, expiry) =
-        row.ok_or_else(|| anyhow::anyhow!("REVEL_SESSION not found in firefox cookies"))?;
+        row.ok_or_else(|| anyhow::anyhow!("{} not found in firefox cookies", target.name))?;
 
     let expires = expiry.and_then(|e| {
         let secs = if e > 1_000_000_000_000 { e / 1000 } else { e };
@@ -76,15 +144,33 @@ fn update_from_firefox(cookies_path: &Path) -> anyhow::Result<()> {
     if let Some(parent) = cookies_path.parent() {
         fs::create_dir_all(parent)?;
     }
-    fs::write(cookies_path, format!("{}\n", line))?;
+    crate::fs::write_atomic(cookies_path, format!("{}\n", line))?;
     Ok(())
 }
 
-fn newest_cookie_db() -> Option<PathBuf> {
+fn firefox_roots() -> Vec<PathBuf> {
+    firefox_roots_on(cfg!(windows), |name| env::var_os(name))
+}
+
+/// The directories to look for `cookies.sqlite` in, with `var` reading the environment.
+fn firefox_roots_on(windows: bool, var: impl Fn(&str) -> Option<OsString>) -> Vec<PathBuf> {
+    let under = |name, dirs: &[&str]| {
+        let base = PathBuf::from(var(name).unwrap_or_default());
+        dirs.iter().map(|dir| base.join(dir)).collect::<Vec<_>>()
+    };
+    if windows {
+        let mut roots = under("APPDATA", FIREFOX_DIRS_WINDOWS);
+        roots.extend(under("LOCALAPPDATA", FIREFOX_DIRS_WINDOWS_LOCAL));
+        roots
+    } else {
+        under("HOME", FIREFOX_DIRS)
+    }
+}
+
+fn newest_cookie_db(roots: &[PathBuf]) -> Option<PathBuf> {
     let mut candidates = Vec::new();
-    for root in FIREFOX_DIRS {
-        let root = PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(root);
-        let _ = collect_cookie_dbs(&root, &mut candidates);
+    for root in roots {
+        let _ = collect_cookie_dbs(root, &mut candidates);
     }
     candidates
         .into_iter()
@@ -114,3 +200,102 @@ fn collect_cookie_dbs(root: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::CookieTarget;
+    use pretty_assertions::assert_eq;
+    use rusqlite::Connection;
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    const CODEFORCES: CookieTarget = CookieTarget {
+        domain: "codeforces.com",
+        name: "JSESSIONID",
+    };
+
+    #[test]
+    fn jar_path() {
+        let cookies_path = Path::new("/home/user/.local/share/cargo-compete/cookies.jsonl");
+        assert_eq!(cookies_path, CookieTarget::default().jar_path(cookies_path));
+        assert_eq!(
+            Path::new("/home/user/.local/share/cargo-compete/cookies.codeforces.com.jsonl"),
+            CODEFORCES.jar_path(cookies_path),
+        );
+    }
+
+    #[test]
+    fn firefox_roots_on() {
+        let var = |name: &str| match name {
+            "HOME" => Some("/home/user".into()),
+            "APPDATA" => Some(r"C:\Users\user\AppData\Roaming".into()),
+            "LOCALAPPDATA" => Some(r"C:\Users\user\AppData\Local".into()),
+            _ => None,
+        };
+        assert_eq!(
+            vec![
+                PathBuf::from("/home/user/.mozilla/firefox"),
+                PathBuf::from("/home/user/snap/firefox/common/.mozilla/firefox"),
+                PathBuf::from("/home/user/.var/app/org.mozilla.firefox/.mozilla/firefox"),
+            ],
+            super::firefox_roots_on(false, var),
+        );
+        let appdata = Path::new(r"C:\Users\user\AppData\Roaming");
+        let local = Path::new(r"C:\Users\user\AppData\Local");
+        assert_eq!(
+            vec![
+                appdata.join("Mozilla/Firefox/Profiles"),
+                local.join("Mozilla/Firefox/Profiles"),
+                local.join(
+                    "Packages/Mozilla.Firefox_n80bbvh6b1yt2/LocalCache/Roaming/Mozilla/Firefox/\
+                     Profiles",
+                ),
+            ],
+            super::firefox_roots_on(true, var),
+        );
+    }
+
+    #[test]
+    fn read_firefox_cookie_db() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-cookie-")
+            .tempdir()?;
+        let db = tempdir.path().join("cookies.sqlite");
+        let conn = Connection::open(&db)?;
+        conn.execute_batch(
+            "CREATE TABLE moz_cookies (
+                host TEXT, name TEXT, value TEXT, path TEXT, expiry INTEGER, lastAccessed INTEGER
+            );
+            INSERT INTO moz_cookies VALUES ('.atcoder.jp', 'REVEL_SESSION', 'old', '/', NULL, 1);
+            INSERT INTO moz_cookies VALUES ('.atcoder.jp', 'REVEL_SESSION', 'new', '/', NULL, 2);
+            INSERT INTO moz_cookies VALUES ('codeforces.com', 'JSESSIONID', 'cf', '/', NULL, 3);
+            INSERT INTO moz_cookies VALUES ('codeforces.com', 'REVEL_SESSION', 'no', '/', NULL, 4);",
+        )?;
+        drop(conn);
+
+        let cookies_path = tempdir.path().join("cookies.jsonl");
+        for (target, raw_cookie) in [
+            (CookieTarget::ATCODER, "REVEL_SESSION=new; HttpOnly; Secure"),
+            (CODEFORCES, "JSESSIONID=cf; HttpOnly; Secure"),
+        ] {
+            let jar_path = target.jar_path(&cookies_path);
+            super::read_firefox_cookie_db(&db, target, &jar_path)?;
+            let line = serde_json::from_str::<serde_json::Value>(&fs::read_to_string(jar_path)?)?;
+            assert_eq!(raw_cookie, line["raw_cookie"]);
+        }
+
+        let missing = CookieTarget {
+            domain: "yukicoder.me",
+            name: "REVEL_SESSION",
+        };
+        let err = super::read_firefox_cookie_db(&db, missing, &missing.jar_path(&cookies_path))
+            .unwrap_err();
+        assert_eq!(
+            "REVEL_SESSION not found in firefox cookies",
+            err.to_string()
+        );
+        Ok(())
+    }
+}
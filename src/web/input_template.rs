@@ -1,15 +1,310 @@
-use crate::shell::Shell;
-use anyhow::Context as _;
-use camino::{Utf8Path, Utf8PathBuf};
+use crate::{
+    naming::{snake, sym_expr},
+    shell::Reporter,
+    web::{
+        judge::{AtCoder, Judge},
+        tasks_print_html::offline,
+        yukicoder_problem_html::INDEX_MARKER,
+    },
+};
+use anyhow::{bail, Context as _};
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
 use heck::KebabCase;
 use regex::Regex;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use std::fs;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    ops::Range,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+};
 
-#[derive(Debug, Clone)]
-struct TaskSection {
-    letter: String,
+/// `input-template` in `cargo-compete.toml`.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub(crate) struct TemplateOptions {
+    /// Wrap the generated code in `mod <letter> { pub fn main() { .. } }` so that several tasks
+    /// can be combined into one file.
+    pub(crate) wrap_in_module: bool,
+    /// Convert `.`/`#` grids into `Vec<Vec<bool>>` right after reading them, instead of only
+    /// suggesting the conversion in a comment.
+    pub(crate) bool_grid: bool,
+    /// Convert grids of digits into `Vec<Vec<usize>>` right after reading them, instead of only
+    /// suggesting the conversion in a comment.
+    pub(crate) digit_grid: bool,
+    pub(crate) input_source: InputSource,
+    /// What reads the input. `input_source` is for proconio only.
+    pub(crate) reader: Reader,
+    /// The directory to put the files in, relative to the package, instead of `src/bin`. Set
+    /// `bin_targets` as well unless Cargo finds them by itself.
+    pub(crate) bin_dir: Option<Utf8PathBuf>,
+    /// File names (relative to `bin_dir`) to use instead of `<letter>.rs`, keyed by task letter.
+    pub(crate) file_name_overrides: HashMap<String, String>,
+    /// Read elements of long arrays as `u32` when the constraints say they fit.
+    pub(crate) compact_ints: bool,
+    /// Prepend `#![allow(clippy::all)]` so that the scaffolding does not trigger lints.
+    pub(crate) allow_clippy: bool,
+    /// AtCoder contest ID, used with `task_slugs` for the task URL comment.
+    #[serde(skip)]
+    pub(crate) contest: Option<String>,
+    /// Task letter to the task ID in its URL (e.g. `A` -> `abc001_a`).
+    #[serde(skip)]
+    pub(crate) task_slugs: HashMap<String, String>,
+    /// Name files `<letter>-<kebab-title>.rs` instead of `<letter>.rs`.
+    pub(crate) name_with_title: bool,
+    /// What to do with arrays of 1-indexed positions (`1 \le A_i \le N`).
+    pub(crate) one_indexed: OneIndexed,
+    /// Target this release line of proconio instead of the one in `TEMPLATE_DEPENDENCIES`.
+    pub(crate) proconio_version: Option<ProconioVersion>,
+    /// Put the input format lines the template was guessed from in a `// format:` comment.
+    pub(crate) format_comment: bool,
+    /// Read arrays the statement calls a permutation with `Usize1`.
+    pub(crate) permutation_usize1: bool,
+    /// Reference what is read in `let _ = (&a, &b, ..);` so that `main` compiles without
+    /// unused-variable warnings, while keeping them for variables added later.
+    pub(crate) suppress_unused: bool,
+    /// What to generate when no line of the input format is understood.
+    pub(crate) fallback: Fallback,
+    /// Run `cargo check` for the written files, and warn about the ones that do not compile.
+    pub(crate) check: bool,
+    /// Add the crates the generated code uses to `[dependencies]` of the package when they are
+    /// missing there. Otherwise they are only shown.
+    pub(crate) add_dependencies: bool,
+    /// Parse the generated code as Rust before writing it, and warn about the tasks that do not
+    /// parse instead. Much faster than `check`, but only catches broken syntax. Without the
+    /// `validate-syntax` feature, nothing is parsed.
+    pub(crate) validate_syntax: bool,
+    /// Add `fn yn(b: bool)` printing `Yes`/`No` to tasks whose output section asks for either.
+    pub(crate) yes_no: bool,
+    /// Add `fn print_array` printing a slice space-separated to tasks whose output section asks
+    /// for a sequence.
+    pub(crate) print_array: bool,
+    /// Write the answers of the testcases to a `BufWriter` flushed once at the end, instead of
+    /// `println!` for each, when they are printed one per line.
+    pub(crate) batch_output: bool,
+    /// Put `#[fastout]` of proconio on `main`, which buffers the output of `println!`. Interactive
+    /// tasks need the output flushed, so leave this off for them.
+    pub(crate) fastout: bool,
+    /// Note that a recursive DFS may overflow the stack when a tree or graph can have this many
+    /// vertices. Defaults to `DEEP_RECURSION_THRESHOLD`.
+    pub(crate) deep_recursion_threshold: Option<u64>,
+    /// Point out a Fenwick tree or a segment tree for queries of the form `1 p x` / `2 l r`.
+    pub(crate) range_query_hint: RangeQueryHint,
+    /// Download the page of a task whose format is not understood, and read the format from it
+    /// instead. Needs `contest` and `task_slugs`.
+    pub(crate) refetch_task_pages: bool,
+    /// The cookies to download the task pages with.
+    #[serde(skip)]
+    pub(crate) cookies_path: PathBuf,
+    /// List the `[[bin]]` entries of the generated files in `GeneratedTemplates::bin_targets`, for
+    /// packages that name their files differently from `src/bin/<letter>.rs`.
+    pub(crate) bin_targets: bool,
+    /// Only replace the first `input!` of existing source files (`--update-input-only`).
+    #[serde(skip)]
+    pub(crate) update_input_only: bool,
+    /// Only generate the tasks that match one of these (`--letters`). See `letter_matches`.
+    #[serde(skip)]
+    pub(crate) letters: Option<Vec<String>>,
+}
+
+/// Where `input!` reads from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum InputSource {
+    /// The default source of `input!`.
+    #[default]
+    Stdin,
+    /// Read the whole stdin into a `String` once, and let every `input!` read from it.
+    ReadAll,
+    /// Let every `input!` read from one `LineSource` over a buffered stdin.
+    Line,
+    /// In debug builds, read `tests/<letter>/1.in` of the package instead of stdin, so that
+    /// `cargo run` works without piping the sample. Release builds read stdin all at once, as
+    /// `input!` does there by default.
+    SampleFile,
+}
+
+/// What the generated code reads the input with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Reader {
+    /// `input!` of proconio.
+    #[default]
+    Proconio,
+    /// A `Scanner` written in the file, for those who do not depend on proconio.
+    Manual,
+}
+
+/// How to read arrays whose elements are 1-indexed positions.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum OneIndexed {
+    /// Read them as they are.
+    #[default]
+    Keep,
+    /// Read them as they are, and add a `// NOTE: a_i are 1-indexed` comment.
+    Annotate,
+    /// Read them with `marker::Usize1`.
+    Usize1,
+    /// Read them as they are, and subtract 1 right after the `input!`.
+    Convert,
+}
+
+/// The template for an input format that could not be read at all.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Fallback {
+    /// An empty `input!` after the format lines in comments.
+    #[default]
+    Input,
+    /// Read the whole stdin into a `String` to be parsed by hand.
+    ReadToString,
+}
+
+/// What to add for point updates and range queries.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum RangeQueryHint {
+    #[default]
+    Off,
+    /// A `// NOTE:` comment.
+    Comment,
+    /// The comment, and a `FenwickTree` of ac-library-rs over the first scalar.
+    AcLibrary,
+}
+
+/// Release lines of proconio to target.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub(crate) enum ProconioVersion {
+    /// The one in the 2020 language update.
+    #[serde(rename = "0.3")]
+    V0_3,
+    /// The one in the 2023 language update.
+    #[serde(rename = "0.4")]
+    V0_4,
+    /// The latest release line.
+    #[serde(rename = "0.5")]
+    V0_5,
+}
+
+impl ProconioVersion {
+    fn requirement(self) -> &'static str {
+        match self {
+            Self::V0_3 => "=0.3.6",
+            Self::V0_4 => "=0.4.5",
+            Self::V0_5 => "=0.5.0",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct GeneratedTemplates {
+    pub(crate) sources: HashMap<Utf8PathBuf, GeneratedSource>,
+    /// The keys of `sources`, in the order of the tasks on the page.
+    pub(crate) order: Vec<Utf8PathBuf>,
+    /// Crates the generated code refers to, as `name => version requirement`. Merge these into
+    /// `[dependencies]` of the package.
+    pub(crate) dependencies: BTreeMap<&'static str, &'static str>,
+    /// The `[[bin]]` entries of `sources`, with `TemplateOptions::bin_targets`.
+    pub(crate) bin_targets: Vec<BinTarget>,
+}
+
+/// A `[[bin]]` entry of a generated file.
+#[derive(Debug, PartialEq)]
+pub(crate) struct BinTarget {
+    /// `<package>-<letter>`, as `cargo compete new` names them, so that the names stay unique in
+    /// a workspace. Just `<letter>` without a `Cargo.toml`.
+    pub(crate) name: String,
+    /// Relative to the package, with `/` as separators.
+    pub(crate) path: String,
+}
+
+/// Formats the entry as TOML to add to `Cargo.toml`.
+impl fmt::Display for BinTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[[bin]]")?;
+        writeln!(f, "name = {}", toml::Value::String(self.name.clone()))?;
+        writeln!(f, "path = {}", toml::Value::String(self.path.clone()))
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct GeneratedSource {
+    /// The letter of the task on the page.
+    pub(crate) letter: String,
+    pub(crate) content: String,
+    /// Fields of `TemplateOptions` that made a difference to this file, in declaration order.
+    pub(crate) applied_options: Vec<&'static str>,
+    /// The `TODO`s and `NOTE`s in `content`, for editors to point at.
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    /// The score of the task, if the page tells.
+    pub(crate) points: Option<u32>,
+}
+
+/// A place in a generated file that was left to be filled in or was guessed with little to go on.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Diagnostic {
+    /// 1-based.
+    pub(crate) line: usize,
+    pub(crate) message: String,
+}
+
+/// `task.html` shorter than this (in bytes) without any task is taken as a broken download.
+const MIN_TASK_HTML_LEN: usize = 100;
+
+/// About as deep as a recursive DFS gets with the default 8 MiB stack.
+const DEEP_RECURSION_THRESHOLD: u64 = 100_000;
+
+// (path prefix in the generated code, crate name, version requirement)
+static TEMPLATE_DEPENDENCIES: &[(&str, &str, &str)] = &[
+    // The ones of the 2023 language update of AtCoder.
+    ("proconio::", "proconio", "=0.4.5"),
+    ("ac_library::", "ac-library-rs", "=0.1.1"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct TaskSection {
+    pub(crate) letter: String,
+    pub(crate) title: String,
     input_blocks: Vec<Vec<String>>,
+    samples: Vec<String>,
+    constraints: Vec<String>,
+    /// The input section has no format text, only an `<img>`.
+    input_is_image: bool,
+    /// The modulus the answer is asked to be reduced by.
+    modulus: Option<Modulus>,
+    /// "配点 : 100 点" or "Score : 100 points".
+    points: Option<u32>,
+    /// The whole task without tags.
+    statement: String,
+    /// The output section without tags.
+    output: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Modulus {
+    /// Like `998244353`, for a `const MOD`.
+    Fixed(u64),
+    /// A symbol of the input, as the name it is read as.
+    Input(String),
+}
+
+/// Layouts of `tasks_print` that `parse_task_sections` was written against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum HtmlSchema {
+    /// `<span class="h2">A - Title</span>` per task and `<h3>入力</h3>` per section.
+    SpanH2,
+}
+
+pub(crate) fn detect_schema(html: &str) -> Option<HtmlSchema> {
+    let markers = [r#"class="h2""#, "<h3>入力</h3>", "<h3>出力</h3>"];
+    markers
+        .iter()
+        .any(|m| html.contains(m))
+        .then_some(HtmlSchema::SpanH2)
 }
 
 fn strip_tags(html: &str) -> String {
@@ -20,21 +315,69 @@ fn strip_tags(html: &str) -> String {
     s = s.replace("&lt;", "<");
     s = s.replace("&gt;", ">");
     s = s.replace("&amp;", "&");
-    s
+    to_half_width(&s)
+}
+
+/// `Ａ_１`, `Ａ，Ｂ` -> `A_1`, `A,B`. Also turns U+3000 into a space.
+fn to_half_width(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+            '\u{3000}' => ' ',
+            c => c,
+        })
+        .collect()
+}
+
+/// Drops `\begin{array}{ll}`/`\end{array}` (or `cases`, ..) and the `&` between the columns, and
+/// splits the rows at `\\`.
+fn unwrap_environment(line: &str) -> Vec<String> {
+    let env_re = Regex::new(r"\\begin\{[A-Za-z*]+\}(?:\{[lcr|]*\})?|\\end\{[A-Za-z*]+\}").unwrap();
+    env_re
+        .replace_all(line, " ")
+        .split(r"\\")
+        .map(|row| {
+            row.replace('&', " ")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// Puts what LaTeX breaks lines with (`\\`, with or without a length as in `\\[2pt]`, and
+/// `\newline`) on lines of their own, for a format written on fewer lines of a `<pre>`. The
+/// spaces (`\ `) between the tokens of such formats are left as plain spaces.
+fn split_latex_line_breaks(text: &str) -> String {
+    let re = Regex::new(r"\\\\(?:\[[^\]\n]*\])?|\\newline\b|\\ ").unwrap();
+    re.replace_all(
+        text,
+        |cap: &regex::Captures<'_>| {
+            if &cap[0] == "\\ " {
+                " "
+            } else {
+                "\n"
+            }
+        },
+    )
+    .into_owned()
 }
 
+// `normalize_line` has stripped the `\mathrm` of `\mathrm{case}` when the line is a lone word.
 fn is_case_placeholder_line(line: &str) -> bool {
     let l = line.to_ascii_lowercase();
-    l.contains("case") && (l.contains('_') || l.contains("\\mathrm"))
+    l.contains("case") && (l.contains('_') || l.contains("\\mathrm") || !l.contains(' '))
 }
 
 fn is_query_placeholder_line(line: &str) -> bool {
     let l = line.to_ascii_lowercase();
-    l.contains("query") && (l.contains('_') || l.contains("\\mathrm") || l.contains("\\text"))
+    l.contains("query")
+        && (l.contains('_') || l.contains("\\mathrm") || l.contains("\\text") || !l.contains(' '))
 }
 
-fn parse_task_sections(task_html: &str) -> Vec<TaskSection> {
-    let span_re = Regex::new(r#"(?s)<span class="h2">\s*([A-Z])\s*-\s*([^<]+)</span>"#)
+pub(crate) fn parse_task_sections(task_html: &str) -> Vec<TaskSection> {
+    // `A`, and also `Ex` or `F2`.
+    let span_re = Regex::new(r#"(?s)<span class="h2">\s*([A-Z][a-z0-9]?)\s*-\s*([^<]+)</span>"#)
         .expect("invalid regex");
     let mut spans: Vec<(usize, usize, String, String)> = Vec::new();
     for cap in span_re.captures_iter(task_html) {
@@ -46,8 +389,12 @@ fn parse_task_sections(task_html: &str) -> Vec<TaskSection> {
 
     let mut out = Vec::new();
     let pre_re = Regex::new(r"(?s)<pre>(.*?)</pre>").expect("invalid regex");
+    let sample_re =
+        Regex::new(r"(?s)<h3>入力例\s*\d*</h3>\s*<pre[^>]*>(.*?)</pre>").expect("invalid regex");
+    // `入力`, `入力 1`, .., but not `入力例`.
+    let input_re = Regex::new(r"<h3>\s*入力([^<]*)</h3>").expect("invalid regex");
     for idx in 0..spans.len() {
-        let (start, _end, letter, _title) = spans[idx].clone();
+        let (start, _end, letter, title) = spans[idx].clone();
         let end = if idx + 1 < spans.len() {
             spans[idx + 1].0
         } else {
@@ -55,77 +402,348 @@ fn parse_task_sections(task_html: &str) -> Vec<TaskSection> {
         };
         let seg = &task_html[start..end];
 
-        let in_pos = seg.find(r"<h3>入力</h3>");
-        if in_pos.is_none() {
+        // Each `入力` header owns everything up to the next `<h3>`, subsections included.
+        let inputs = input_re
+            .captures_iter(seg)
+            .filter(|cap| !cap[1].trim_start().starts_with('例'))
+            .map(|cap| {
+                let header = cap.get(0).unwrap();
+                let end = seg[header.end()..]
+                    .find("<h3>")
+                    .map_or(seg.len(), |i| header.end() + i);
+                (header.start(), &seg[header.end()..end])
+            })
+            .collect::<Vec<_>>();
+        let Some(&(in_pos, _)) = inputs.first() else {
             continue;
-        }
-        let in_pos = in_pos.unwrap();
-        let out_pos = seg.find(r"<h3>出力</h3>").unwrap_or(seg.len());
-        let inp = &seg[in_pos..out_pos];
+        };
+        let inp = inputs.iter().map(|&(_, inp)| inp).collect::<String>();
 
         let mut blocks: Vec<Vec<String>> = Vec::new();
-        for cap in pre_re.captures_iter(inp) {
+        for cap in pre_re.captures_iter(&inp) {
             let pre = cap.get(1).unwrap().as_str();
-            let txt = strip_tags(pre);
+            let txt = split_latex_line_breaks(&strip_tags(pre));
             let lines: Vec<String> = txt
                 .lines()
-                .map(|l| l.trim())
+                .flat_map(unwrap_environment)
                 .filter(|l| !l.is_empty())
-                .map(|l| l.to_string())
                 .collect();
             blocks.push(lines);
         }
+        let input_is_image = inp.contains("<img") && blocks.iter().all(|b| b.is_empty());
+        let samples = sample_re
+            .captures_iter(seg)
+            .map(|cap| strip_tags(cap.get(1).unwrap().as_str()))
+            .collect();
+        let statement = strip_tags(seg);
+        let output = match seg.find("<h3>出力</h3>") {
+            Some(pos) => {
+                let body = &seg[pos + "<h3>出力</h3>".len()..];
+                strip_tags(&body[..body.find("<h3>").unwrap_or(body.len())])
+            }
+            None => String::new(),
+        };
+        let constraints = match seg.find(r"<h3>制約</h3>") {
+            Some(pos) if pos < in_pos => strip_tags(&seg[pos..in_pos])
+                .lines()
+                .map(|l| l.trim())
+                .filter(|l| !l.is_empty() && *l != "制約")
+                .map(|l| l.to_string())
+                .collect(),
+            _ => vec![],
+        };
+        let modulus = find_modulus(&statement, &input_symbols(&blocks));
         out.push(TaskSection {
             letter,
+            title,
             input_blocks: blocks,
+            samples,
+            constraints,
+            input_is_image,
+            modulus,
+            points: find_points(&statement),
+            statement,
+            output,
         });
     }
     out
 }
 
-fn snake(s: &str) -> String {
-    let mut out = String::new();
-    let mut prev_is_underscore = false;
-    for ch in s.chars() {
-        let c = if ch.is_ascii_alphanumeric() { ch } else { '_' };
-        if c == '_' {
-            if !prev_is_underscore {
-                out.push('_');
-            }
-            prev_is_underscore = true;
-        } else {
-            out.push(c.to_ascii_lowercase());
-            prev_is_underscore = false;
-        }
+fn find_points(statement: &str) -> Option<u32> {
+    let re = Regex::new(r"(?:配点|Score)\s*:\s*(\d+)").unwrap();
+    re.captures(statement)?[1].parse().ok()
+}
+
+/// The bare symbols of the input format, like `N` and `P` of `N P`.
+fn input_symbols(blocks: &[Vec<String>]) -> HashSet<String> {
+    blocks
+        .iter()
+        .flatten()
+        .flat_map(|l| {
+            normalize_line(l)
+                .split_whitespace()
+                .filter(|t| t.chars().all(|c| c.is_ascii_alphanumeric()))
+                .filter(|t| t.starts_with(|c: char| c.is_ascii_alphabetic()))
+                .map(ToOwned::to_owned)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Finds "modulo 998244353" or "10^9+7 で割った余り" in the statement, or "modulo P" and
+/// "P で割った余り" for `P` in `symbols`.
+fn find_modulus(text: &str, symbols: &HashSet<String>) -> Option<Modulus> {
+    let lower = text.to_lowercase();
+    if !(lower.contains("mod") || text.contains("余り")) {
+        return None;
+    }
+    // Before the numbers, which may only be bounds of `P`.
+    let symbol_re = Regex::new(
+        r"(?i)(?:\\[bp]?mod|\bmod(?:ulo)?)\s*\{?\s*([A-Za-z][A-Za-z0-9]*)\b|([A-Za-z][A-Za-z0-9]*)\s*で割った余り",
+    )
+    .unwrap();
+    let symbol = symbol_re.captures_iter(text).find_map(|cap| {
+        let sym = cap.get(1).or_else(|| cap.get(2))?.as_str();
+        symbols.contains(sym).then(|| Modulus::Input(snake(sym)))
+    });
+    if symbol.is_some() {
+        return symbol;
+    }
+    let re = Regex::new(r"998\s*,?\s*244\s*,?\s*353|1\s*,?\s*000\s*,?\s*000\s*,?\s*007|10\s*\^\s*\{?\s*9\s*\}?\s*\+\s*7")
+        .unwrap();
+    let m = re.find(text)?;
+    if m.as_str().starts_with("998") {
+        Some(Modulus::Fixed(998_244_353))
+    } else {
+        Some(Modulus::Fixed(1_000_000_007))
     }
-    out.trim_matches('_').to_string()
 }
 
-fn sym_expr(s: &str) -> String {
-    // Convert common AtCoder latex-ish symbols to a Rust-ish expression: N-1, 5N, etc.
-    let mut t = s.trim().replace(' ', "");
-    t = t.replace('\\', "");
-    if let Some((a, b)) = t.split_once('-') {
-        if b.chars().all(|c| c.is_ascii_digit()) {
-            return format!("{}-{}", snake(a), b);
-        }
+pub(crate) fn parse_yukicoder_sections(html: &str) -> Vec<TaskSection> {
+    let title_re =
+        Regex::new(r"(?s)<title>\s*(?:No\.\d+\s+)?(.*?)(?:\s+-\s+yukicoder)?\s*</title>")
+            .expect("invalid regex");
+    // The first one is the format, and the rest are the samples.
+    let pre_re =
+        Regex::new(r"(?s)<h\d>\s*入力\s*</h\d>\s*<pre[^>]*>(.*?)</pre>").expect("invalid regex");
+    let output_re =
+        Regex::new(r"(?s)<h\d>\s*出力\s*</h\d>(.*?)(?:<h\d>|$)").expect("invalid regex");
+
+    let mut out = vec![];
+    for page in html.split(INDEX_MARKER).skip(1) {
+        let Some((letter, page)) = page.split_once(" -->") else {
+            continue;
+        };
+        let title = title_re
+            .captures(page)
+            .map(|cap| strip_tags(&cap[1]))
+            .unwrap_or_default();
+        let mut pres = pre_re.captures_iter(page).map(|cap| strip_tags(&cap[1]));
+        let Some(format) = pres.next() else {
+            continue;
+        };
+        let lines: Vec<String> = format
+            .lines()
+            .flat_map(|l| {
+                unwrap_environment(&l.replace('$', "").replace("\\(", "").replace("\\)", ""))
+            })
+            .filter(|l| !l.is_empty())
+            .collect();
+        let statement = strip_tags(page);
+        let modulus = find_modulus(&statement, &input_symbols(std::slice::from_ref(&lines)));
+        let output = output_re
+            .captures(page)
+            .map(|cap| strip_tags(&cap[1]))
+            .unwrap_or_default();
+        out.push(TaskSection {
+            letter: letter.trim().to_owned(),
+            title,
+            input_blocks: vec![lines],
+            samples: pres.collect(),
+            constraints: vec![],
+            input_is_image: false,
+            modulus,
+            points: None,
+            statement,
+            output,
+        });
     }
-    // 5N form
-    let coef_re = Regex::new(r"^(\d+)([A-Za-z]+)$").unwrap();
-    if let Some(cap) = coef_re.captures(&t) {
-        return format!("{}*{}", &cap[1], snake(&cap[2]));
+    out
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Bounds {
+    lower: Option<i128>,
+    upper: Option<i128>,
+    /// The symbol the upper bound was given by, like `n` for `A_i \le N`.
+    upper_symbol: Option<String>,
+}
+
+fn parse_number(s: &str) -> Option<i128> {
+    // 998244353, -10^9, 2 \times 10^5, 10^{18}
+    let t = s
+        .replace("\\times", "*")
+        .replace("\\cdot", "*")
+        .chars()
+        .filter(|c| !matches!(c, '{' | '}' | ' ' | ','))
+        .collect::<String>();
+    let (neg, t) = match t.strip_prefix('-') {
+        Some(t) => (true, t),
+        None => (false, &*t),
+    };
+    let mut value: i128 = 1;
+    for factor in t.split('*') {
+        let factor = match factor.split_once('^') {
+            Some((b, e)) => b.parse::<i128>().ok()?.checked_pow(e.parse().ok()?)?,
+            None => factor.parse().ok()?,
+        };
+        value = value.checked_mul(factor)?;
+    }
+    Some(if neg { -value } else { value })
+}
+
+/// Collects `lo \le X \le hi` style constraints, keyed by the snake-cased base symbol.
+fn parse_constraints(lines: &[String]) -> HashMap<String, Bounds> {
+    let cmp_re = Regex::new(r"\\le(?:qq?)?|\\lt|≦|≤|<").unwrap();
+    let sym_re = Regex::new(r"^([A-Za-z]+\d*)(?:_.*)?$").unwrap();
+
+    let mut bounds: HashMap<String, Bounds> = HashMap::new();
+    // Upper bounds given by another symbol, like `1 \le A_i \le N`.
+    let mut symbolic_upper: Vec<(String, String)> = Vec::new();
+    for line in lines {
+        // `\left` would otherwise look like `\le`.
+        let line = line.replace("\\left", "").replace("\\right", "");
+        let parts = cmp_re.split(&line).map(str::trim).collect::<Vec<_>>();
+        let (lower, syms, upper) = match *parts {
+            [lo, syms, hi] => (Some(lo), syms, Some(hi)),
+            [syms, hi] if parse_number(syms).is_none() => (None, syms, Some(hi)),
+            [lo, syms] => (Some(lo), syms, None),
+            _ => continue,
+        };
+        for sym in syms.split(',') {
+            let Some(cap) = sym_re.captures(sym.trim()) else {
+                continue;
+            };
+            let name = snake(&cap[1]);
+            let entry = bounds.entry(name.clone()).or_default();
+            if let Some(lo) = lower.and_then(parse_number) {
+                entry.lower.get_or_insert(lo);
+            }
+            match upper.map(|hi| (hi, parse_number(hi))) {
+                Some((_, Some(hi))) => {
+                    entry.upper.get_or_insert(hi);
+                }
+                Some((hi, None)) => {
+                    entry.upper_symbol.get_or_insert_with(|| snake(hi));
+                    symbolic_upper.push((name, snake(hi)));
+                }
+                None => {}
+            }
+        }
     }
-    if t.chars().all(|c| c.is_ascii_alphabetic()) {
-        return snake(&t);
+    for (name, by) in symbolic_upper {
+        if let Some(hi) = bounds.get(&by).and_then(|b| b.upper) {
+            bounds.entry(name).or_default().upper.get_or_insert(hi);
+        }
     }
-    t
+    bounds
 }
 
 fn is_string_symbol(sym: &str) -> bool {
     matches!(sym.to_ascii_uppercase().as_str(), "S" | "T" | "U" | "X")
 }
 
-fn parse_1d_array_line(line: &str) -> Option<(String, String)> {
+/// What the statement says `sym` is, as in "英小文字からなる文字列 S" or "X is an integer".
+/// `Some(true)` for a string of letters, `Some(false)` for an integer.
+fn statement_says_string(sym: &str, ctx: &GuessContext<'_>) -> Option<bool> {
+    let sym = regex::escape(sym);
+    let mentions = |keywords: &str| {
+        let keyword_first = Regex::new(&format!(
+            r"(?:{keywords})[\s$(\\]{{0,3}}{sym}(?:[^A-Za-z0-9_]|$)"
+        ))
+        .unwrap();
+        let symbol_first = Regex::new(&format!(
+            r"(?:^|[^A-Za-z0-9_]){sym}\s*(?:は|が|(?i:is|consists|contains))[^.。]{{0,24}}(?:{keywords})"
+        ))
+        .unwrap();
+        keyword_first.is_match(ctx.statement) || symbol_first.is_match(ctx.statement)
+    };
+    if mentions(r"英小文字|英大文字|英字|文字列|(?i:lowercase|uppercase|English letters|string)")
+    {
+        Some(true)
+    } else if mentions(r"整数|(?i:integer)") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// The length of the string `sym` as `= n` or `<= 2*10^5`, from "|S| = N", "長さ N の文字列 S" or
+/// "|S| \le 2 \times 10^5".
+fn string_length(sym: &str, ctx: &GuessContext<'_>) -> Option<String> {
+    let sym = regex::escape(sym);
+    let eq_re = Regex::new(&format!(
+        r"\|{sym}\|\s*=\s*([A-Za-z0-9+\-]+)|長さ\s*([A-Za-z0-9+\-]+)\s*の[^。\s]{{0,12}}文字列\s*{sym}(?:[^A-Za-z0-9_]|$)"
+    ))
+    .unwrap();
+    if let Some(cap) = eq_re.captures(ctx.statement) {
+        let len = cap.get(1).or_else(|| cap.get(2)).unwrap().as_str();
+        return Some(format!("= {}", sym_expr(len)));
+    }
+    let le_re = Regex::new(&format!(
+        r"(?m)\|{sym}\|\s*(?:\\leq?|≤|≦)\s*([^,。$\n]+?)\s*(?:$|[,。$])"
+    ))
+    .unwrap();
+    let cap = le_re.captures(ctx.statement)?;
+    Some(format!("<= {}", sym_expr(&cap[1])))
+}
+
+/// Whether the elements of `base` may be read as `u32`, given the length expression.
+fn fits_compact(base: &str, len_expr: &str, ctx: &GuessContext<'_>) -> bool {
+    const LONG: i128 = 200_000;
+
+    let len_sym = len_expr
+        .trim_start_matches(|c: char| !c.is_ascii_alphabetic())
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or("");
+    let is_long = ctx
+        .bounds
+        .get(len_sym)
+        .and_then(|b| b.upper)
+        .is_some_and(|n| n >= LONG);
+    let fits = ctx.bounds.get(base).is_some_and(|b| {
+        matches!(
+            (b.lower, b.upper),
+            (Some(lo), Some(hi)) if lo >= 0 && hi <= i128::from(u32::MAX)
+        )
+    });
+    is_long && fits
+}
+
+/// Whether the elements of `base` are 1-indexed positions, like `1 \le A_i \le N`.
+fn is_one_indexed(base: &str, ctx: &GuessContext<'_>) -> bool {
+    ctx.bounds
+        .get(base)
+        .is_some_and(|b| b.lower == Some(1) && b.upper_symbol.is_some())
+}
+
+/// Whether the statement calls `name` (`p` for `P`) a permutation, as in "順列 P" or
+/// "P is a permutation".
+fn is_permutation(name: &str, ctx: &GuessContext<'_>) -> bool {
+    let sym = regex::escape(&name.to_uppercase());
+    let keyword_first = Regex::new(&format!(
+        r"(?i:permutation|順列)[\s$(=]{{0,3}}{sym}(?:[^A-Za-z0-9_]|$)"
+    ))
+    .unwrap();
+    let symbol_first = Regex::new(&format!(
+        r"(?:^|[^A-Za-z0-9_]){sym}[^A-Za-z0-9_.。][^.。]{{0,12}}(?i:permutation|順列)"
+    ))
+    .unwrap();
+    keyword_first.is_match(ctx.statement) || symbol_first.is_match(ctx.statement)
+}
+
+fn parse_1d_array_line(line: &str, ctx: &GuessContext<'_>) -> Option<(String, String)> {
     // A_1 A_2 \ldots A_N  or A_0 ... A_{N-1}
     let ln = line
         .replace("\\cdots", "\\ldots")
@@ -133,7 +751,7 @@ fn parse_1d_array_line(line: &str) -> Option<(String, String)> {
     // NOTE: Rust's `regex` crate does NOT support backreferences like \1.
     // Capture the base name three times and validate equality in code.
     let re = Regex::new(
-        r"^([A-Za-z]+)_(?:\{)?(\d+)(?:\})?\s+([A-Za-z]+)_(?:\{)?(\d+)(?:\})?\s+\\ldots\s+([A-Za-z]+)_(?:\{)?(.+?)(?:\})?$",
+        r"^([A-Za-z]+\d*)_(?:\{)?(\d+)(?:\})?\s+([A-Za-z]+\d*)_(?:\{)?(\d+)(?:\})?\s+\\ldots\s+([A-Za-z]+\d*)_(?:\{)?(.+?)(?:\})?$",
     )
     .unwrap();
     let cap = re.captures(&ln)?;
@@ -151,38 +769,119 @@ fn parse_1d_array_line(line: &str) -> Option<(String, String)> {
         .trim_matches('{')
         .trim_matches('}');
     let len_expr = if first_idx == "0" {
-        // if last is N-1, length is N; else (last+1)
-        let mm = Regex::new(r"^([A-Za-z]+)-1$").unwrap();
+        // if last is N-1 or 2N-1, length is N or 2N; else (last+1)
+        let mm = Regex::new(r"^(.+?)\s*-\s*1$").unwrap();
         if let Some(c2) = mm.captures(last_raw) {
-            snake(c2.get(1).unwrap().as_str())
+            sym_expr(c2.get(1).unwrap().as_str())
         } else {
             format!("({})+1", sym_expr(last_raw))
         }
     } else {
         sym_expr(last_raw)
     };
-    Some((snake(base1), format!("[usize; {}]", len_expr)))
+    let name = snake(base1);
+    let values = ctx.sample_values.get(&name).filter(|v| !v.is_empty());
+    // Without samples, only `S_i` is taken for words. `T_i` and `X_i` are usually numbers.
+    let is_string = match values {
+        Some(values) => values.iter().any(|v| v.parse::<f64>().is_err()),
+        None => name == "s",
+    };
+    let elem = if is_string {
+        "Chars"
+    } else if let Some(ty) = values.and_then(|values| sample_element_type(values)) {
+        ty
+    } else if ctx.options.one_indexed == OneIndexed::Usize1 && is_one_indexed(&name, ctx)
+        || ctx.options.permutation_usize1 && is_permutation(&name, ctx)
+    {
+        "Usize1"
+    } else if ctx.options.compact_ints && fits_compact(&name, &len_expr, ctx) {
+        "u32"
+    } else {
+        "usize"
+    };
+    Some((name, format!("[{}; {}]", elem, len_expr)))
+}
+
+/// The sample values of each array read from a line like `A_1 A_2 \ldots A_N`, up to the first
+/// line the sample rows may not line up with.
+fn sample_array_values(lines: &[String], samples: &[String]) -> HashMap<String, Vec<String>> {
+    let base_re = Regex::new(r"^([A-Za-z]+\d*)_").unwrap();
+    let mut values: HashMap<String, Vec<String>> = HashMap::new();
+    for (i, line) in lines.iter().map(|l| normalize_line(l)).enumerate() {
+        if line.contains("\\vdots")
+            || is_case_placeholder_line(&line)
+            || is_query_placeholder_line(&line)
+        {
+            break;
+        }
+        if !["\\ldots", "\\cdots", "\\dots"]
+            .iter()
+            .any(|dots| line.contains(dots))
+        {
+            continue;
+        }
+        let Some(cap) = base_re.captures(&line) else {
+            continue;
+        };
+        let name = snake(&cap[1]);
+        for sample in samples {
+            if let Some(row) = sample.lines().nth(i) {
+                let row = row.split_whitespace().map(ToOwned::to_owned);
+                values.entry(name.clone()).or_default().extend(row);
+            }
+        }
+    }
+    values
+}
+
+/// `f64` or `i64` if the sample values of an array have decimals or negatives.
+fn sample_element_type(values: &[String]) -> Option<&'static str> {
+    if values.iter().any(|v| v.parse::<f64>().is_err()) {
+        None
+    } else if values.iter().any(|v| v.parse::<i64>().is_err()) {
+        Some("f64")
+    } else if values.iter().any(|v| v.starts_with('-')) {
+        Some("i64")
+    } else {
+        None
+    }
 }
 
-fn parse_pair_repeat(lines: &[String], idx: usize) -> Option<(String, String, usize)> {
+fn parse_pair_repeat(
+    lines: &[String],
+    idx: usize,
+    ctx: &GuessContext<'_>,
+) -> Option<(String, String, usize)> {
     // x_1 y_1  ... x_M y_M
-    let re = Regex::new(r"^([A-Za-z]+)_\{?\d+\}?\s+([A-Za-z]+)_\{?\d+\}?$").unwrap();
-    let cap = re.captures(lines.get(idx)?)?;
-    let a = cap.get(1)?.as_str();
-    let b = cap.get(2)?.as_str();
+    let col_re = Regex::new(r"^([A-Za-z]+\d*)_\{?\d+\}?$").unwrap();
+    let bases = lines
+        .get(idx)?
+        .split_whitespace()
+        .map(|tok| col_re.captures(tok).map(|cap| cap[1].to_owned()))
+        .collect::<Option<Vec<_>>>()?;
+    // `a_1 a_1` is not a tuple we can name.
+    if bases.len() < 2 || bases.iter().collect::<HashSet<_>>().len() < bases.len() {
+        return None;
+    }
 
     let last_re = Regex::new(&format!(
-        r"^{}_(?:\{{)?(.+?)(?:\}})?\s+{}_(?:\{{)?(.+?)(?:\}})?$",
-        regex::escape(a),
-        regex::escape(b)
+        r"^{}$",
+        bases
+            .iter()
+            .map(|b| format!(r"{}_(?:\{{)?(.+?)(?:\}})?", regex::escape(b)))
+            .collect::<Vec<_>>()
+            .join(r"\s+"),
     ))
     .unwrap();
 
     let mut count_expr: Option<String> = None;
     let mut last_found: Option<usize> = None;
+    // Whether every row is written out (`a_1 b_1`, `a_2 b_2`, `a_3 b_3`) without `\vdots`.
+    let mut enumerated = true;
     let mut j = idx + 1;
     while j < lines.len() && j < idx + 12 {
         if lines[j].contains("\\vdots") {
+            enumerated = false;
             j += 1;
             continue;
         }
@@ -195,59 +894,229 @@ fn parse_pair_repeat(lines: &[String], idx: usize) -> Option<(String, String, us
         if last_found.is_some() {
             break;
         }
+        enumerated = false;
         j += 1;
     }
-    let count_expr = count_expr?;
-    let count_expr = sym_expr(count_expr.trim_matches('{').trim_matches('}'));
-    let consumed = last_found.map(|lf| lf + 1 - idx).unwrap_or(1);
-    let name = snake(&(a.to_string() + b));
-    Some((name, format!("[(usize, usize); {}]", count_expr), consumed))
+    let consumed = last_found? + 1 - idx;
+    let count_expr = if enumerated {
+        consumed.to_string()
+    } else {
+        sym_expr(count_expr?.trim_matches('{').trim_matches('}'))
+    };
+    let name = snake(&bases.concat());
+    let (mut types, note) = match sample_column_types(lines, idx, bases.len(), ctx.samples) {
+        Some(types) => (types, ""),
+        None => (
+            vec!["usize"; bases.len()],
+            " // NOTE: no sample to check the column types",
+        ),
+    };
+    // Coordinates like `-10^9 \le X_i, Y_i \le 10^9` may be negative where no sample shows it.
+    for (ty, base) in types.iter_mut().zip(&bases) {
+        let signed = ctx
+            .bounds
+            .get(&snake(base))
+            .is_some_and(|b| b.lower.is_some_and(|lo| lo < 0));
+        if *ty == "usize" && signed {
+            *ty = "i64";
+        }
+    }
+    let ty = format!("[({}); {count_expr}],{note}", types.join(", "));
+    Some((name, ty, consumed))
 }
 
-fn parse_vertical_scalars(lines: &[String], idx: usize) -> Option<(String, String, usize)> {
-    // B_1 \vdots B_N  -> b: [usize; n]
-    let re = Regex::new(r"^([A-Za-z]+)_(?:\{)?1(?:\})?$").unwrap();
-    let cap = re.captures(lines.get(idx)?)?;
-    let base = cap.get(1)?.as_str();
-    if base.eq_ignore_ascii_case("S") {
+/// The types of the columns of the row at `idx` (`usize`, `i64`, `char` or `String`), judging
+/// from the same row of the samples. The row is only found if every line before it is one row.
+fn sample_column_types(
+    lines: &[String],
+    idx: usize,
+    columns: usize,
+    samples: &[String],
+) -> Option<Vec<&'static str>> {
+    if lines[..idx].iter().any(|l| l.contains("\\vdots")) {
         return None;
     }
-    let last_re = Regex::new(&format!(r"^{}_(?:\{{)?(.+?)(?:\}})?$", regex::escape(base))).unwrap();
-    let mut last: Option<String> = None;
-    let mut last_found: Option<usize> = None;
-    let mut j = idx + 1;
-    while j < lines.len() && j < idx + 8 {
-        if lines[j].contains("\\vdots") {
-            j += 1;
+    let mut types: Option<Vec<&'static str>> = None;
+    for sample in samples {
+        let Some(row) = sample.lines().nth(idx) else {
+            continue;
+        };
+        let row = row.split_whitespace().collect::<Vec<_>>();
+        if row.len() != columns {
             continue;
         }
-        if let Some(c2) = last_re.captures(&lines[j]) {
-            last = Some(c2.get(1).unwrap().as_str().to_string());
-            last_found = Some(j);
+        let types = types.get_or_insert_with(|| vec!["usize"; columns]);
+        for (ty, value) in types.iter_mut().zip(row) {
+            let this = if value.parse::<u64>().is_ok() {
+                "usize"
+            } else if value.parse::<i64>().is_ok() {
+                "i64"
+            } else if value.chars().count() == 1 {
+                "char"
+            } else {
+                "String"
+            };
+            // `String` > `char` > `i64` > `usize`, since a wider one can hold what the others read.
+            let rank = |t: &str| {
+                ["usize", "i64", "char", "String"]
+                    .iter()
+                    .position(|&x| x == t)
+            };
+            if rank(this) > rank(ty) {
+                *ty = this;
+            }
+        }
+    }
+    types
+}
+
+/// Finds the last row of `base_1` / `base_2` / `\vdots` / `base_N` starting at `idx`, and returns
+/// its subscript without braces together with the number of lines up to it.
+///
+/// Lines that are not a subscripted symbol (`\vdots`, `:`, ..) are skipped, but another symbol
+/// ends the search.
+fn find_vertical_last(lines: &[String], idx: usize, base: &str) -> Option<(String, usize)> {
+    let row_re = Regex::new(r"^([A-Za-z]+\d*)_(?:\{([^{}]*)\}|(\S+))$").unwrap();
+    let mut last: Option<(String, usize)> = None;
+    for (j, line) in lines.iter().enumerate().take(idx + 8).skip(idx + 1) {
+        let Some(cap) = row_re.captures(line.trim()) else {
+            continue;
+        };
+        if &cap[1] != base {
             break;
         }
-        j += 1;
+        let sub = cap.get(2).or_else(|| cap.get(3)).unwrap().as_str();
+        last = Some((sub.split_whitespace().collect(), j + 1 - idx));
     }
-    let last = last?;
-    let count_expr = sym_expr(last.trim_matches('{').trim_matches('}'));
-    let consumed = last_found.map(|lf| lf + 1 - idx).unwrap_or(1);
+    last
+}
+
+fn parse_vertical_scalars(lines: &[String], idx: usize) -> Option<(String, String, usize)> {
+    // B_1 \vdots B_N  -> b: [usize; n]
+    let re = Regex::new(r"^([A-Za-z]+\d*)_(?:\{)?1(?:\})?$").unwrap();
+    let cap = re.captures(lines.get(idx)?)?;
+    let base = cap.get(1)?.as_str();
+    if base.eq_ignore_ascii_case("S") {
+        return None;
+    }
+    let (last, consumed) = find_vertical_last(lines, idx, base)?;
+    let count_expr = sym_expr(&last);
     Some((snake(base), format!("[usize; {}]", count_expr), consumed))
 }
 
+/// What the rows of a grid hold.
+#[derive(Debug, PartialEq)]
+enum GridCells {
+    Chars,
+    /// Digits without spaces, like `0123`, read as `Chars`.
+    Digits,
+    /// Numbers separated by spaces, read as a matrix of the given element type.
+    Numbers(&'static str),
+}
+
 fn parse_grid_lines(
     lines: &[String],
     idx: usize,
     known_h: Option<&str>,
-) -> Option<(String, String, usize)> {
+    known_w: Option<&str>,
+    samples: &[String],
+) -> Option<(String, String, GridCells, usize)> {
+    // Returns the name, the row count, what the rows hold, and the number of lines consumed.
     // S_1 \vdots S_H  -> s: [Chars; h]
-    let re = Regex::new(r"^([A-Za-z]+)_(?:\{)?1(?:\})?$").unwrap();
-    let cap = re.captures(lines.get(idx)?)?;
+    // S1_1 \vdots S1_H  -> s1: [Chars; h]
+    // G_1 \vdots G_H  -> g: [Chars; h], once `H` and `W` are known
+    let re = Regex::new(r"^([A-Za-z]\d?)_(?:\{)?1(?:\})?$").unwrap();
+    // A row of column numbers right above `S_1` only labels the columns.
+    let header = usize::from(is_column_header(lines.get(idx)?));
+    let start = idx + header;
+    let cap = re.captures(lines.get(start)?)?;
+    let base = cap.get(1)?.as_str();
+    let (last, consumed) = find_vertical_last(lines, start, base)?;
+    let is_s = base[..1].eq_ignore_ascii_case("s");
+    // Other names are only grids when the rows run to the height and the width is known.
+    if !is_s && (known_w.is_none() || known_h != Some(&*sym_expr(&last))) {
+        return None;
+    }
+
+    // The first row of the grid in each sample, assuming the lines above it are one per line.
+    let rows = samples
+        .iter()
+        .filter_map(|s| s.lines().nth(start))
+        .map(|row| row.split_whitespace().map(ToOwned::to_owned).collect())
+        .collect::<Vec<Vec<String>>>();
+    let is_number = |t: &String| t.parse::<f64>().is_ok();
+    let is_digits = |r: &Vec<String>| match &r[..] {
+        [row] => row.bytes().all(|b| b.is_ascii_digit()),
+        _ => false,
+    };
+    // Rows of one number each are a grid of digits only if as long as the width in the sample.
+    let width = known_w.and_then(|w| sample_scalar(&lines[..start], w, samples));
+    let spans_width = || {
+        width
+            .as_ref()
+            .is_some_and(|width| rows.iter().all(|r| r[0].len().to_string() == **width))
+    };
+    let cells = if known_w.is_some()
+        && !rows.is_empty()
+        && rows.iter().all(|r| r.len() >= 2 && r.iter().all(is_number))
+    {
+        GridCells::Numbers(sample_element_type(&rows.concat()).unwrap_or("usize"))
+    } else if known_w.is_some()
+        && !rows.is_empty()
+        && rows.iter().all(is_digits)
+        && (is_s || spans_width())
+    {
+        GridCells::Digits
+    } else if !is_s && !rows.is_empty() && rows.iter().all(|r| r.iter().all(is_number)) {
+        // `B_1 \vdots B_H` of one number each
+        return None;
+    } else {
+        GridCells::Chars
+    };
+    let h_expr = known_h
+        .map(|h| h.to_string())
+        .unwrap_or_else(|| sym_expr(&last));
+    Some((snake(base), h_expr, cells, header + consumed))
+}
+
+/// The value of the scalar `name` in the first sample that has it, for scalars read one line per
+/// format line as in `H W`.
+fn sample_scalar(lines: &[String], name: &str, samples: &[String]) -> Option<String> {
+    lines.iter().enumerate().find_map(|(i, line)| {
+        let pos = line.split_whitespace().position(|t| snake(t) == name)?;
+        samples
+            .iter()
+            .find_map(|s| s.lines().nth(i)?.split_whitespace().nth(pos))
+            .map(ToOwned::to_owned)
+    })
+}
+
+/// `1 2 \ldots W`, `1 2 3 4 5` or `12345`.
+fn is_column_header(line: &str) -> bool {
+    let dots_re = Regex::new(r"^1 2 (?:\\[lc]?dots|\.\.\.) [A-Za-z0-9]+$").unwrap();
+    let numbers_re = Regex::new(r"^\d+(?: \d+)*$").unwrap();
+    dots_re.is_match(line) || numbers_re.is_match(line)
+}
+
+fn parse_matrix_lines(lines: &[String], idx: usize) -> Option<(String, String, usize)> {
+    // A_{1,1} A_{1,2} \ldots A_{1,W} \vdots A_{H,1} A_{H,2} \ldots A_{H,W}  -> a: [[usize; w]; h]
+    let ln = lines
+        .get(idx)?
+        .replace("\\cdots", "\\ldots")
+        .replace("\\dots", "\\ldots");
+    let re = Regex::new(
+        r"^([A-Za-z]+\d*)_\{1,\s*1\}\s+([A-Za-z]+\d*)_\{1,\s*2\}\s+\\ldots\s+([A-Za-z]+\d*)_\{1,\s*(.+?)\}$",
+    )
+    .unwrap();
+    let cap = re.captures(&ln)?;
     let base = cap.get(1)?.as_str();
-    if !base.eq_ignore_ascii_case("S") {
+    if base != cap.get(2)?.as_str() || base != cap.get(3)?.as_str() {
         return None;
     }
-    let last_re = Regex::new(r"^S_(?:\{)?(.+?)(?:\})?$").unwrap();
-    let mut last: Option<String> = None;
+    let w_raw = cap.get(4)?.as_str().to_string();
+
+    let last_re = Regex::new(&format!(r"^{}_\{{(.+?),\s*1\}}\s", regex::escape(base))).unwrap();
+    let mut h_raw: Option<String> = None;
     let mut last_found: Option<usize> = None;
     let mut j = idx + 1;
     while j < lines.len() && j < idx + 8 {
@@ -256,64 +1125,412 @@ fn parse_grid_lines(
             continue;
         }
         if let Some(c2) = last_re.captures(&lines[j]) {
-            last = Some(c2.get(1).unwrap().as_str().to_string());
+            h_raw = Some(c2.get(1).unwrap().as_str().to_string());
             last_found = Some(j);
+        } else if last_found.is_some() {
             break;
         }
         j += 1;
     }
-    let last = last?;
-    let h_expr = known_h
-        .map(|h| h.to_string())
-        .unwrap_or_else(|| sym_expr(last.trim_matches('{').trim_matches('}')));
+    // The dimensions come from the subscripts, so `N K` gives `[[usize; k]; n]` even after `H W`.
+    let h_expr = sym_expr(&h_raw?);
+    let w_expr = sym_expr(&w_raw);
     let consumed = last_found.map(|lf| lf + 1 - idx).unwrap_or(1);
-    Some((snake(base), format!("[Chars; {}]", h_expr), consumed))
+    Some((
+        snake(base),
+        format!("[[usize; {}]; {}]", w_expr, h_expr),
+        consumed,
+    ))
 }
 
-fn guess_input_from_lines(lines: &[String]) -> (Vec<String>, bool) {
-    let mut decls: Vec<String> = Vec::new();
-    let mut needs_chars = false;
-    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
-    let mut known_h: Option<String> = None;
+/// Where the length of each row of a jagged array comes from.
+#[derive(Debug, PartialEq)]
+enum JaggedLen {
+    /// `K_i A_{i,1} \ldots A_{i,K_i}`, or `K_i` on its own line before the row.
+    Prefixed(String),
+    /// An array read earlier, as in `K_1 \ldots K_N` followed by `A_{i,1} \ldots A_{i,K_i}`.
+    Earlier(String),
+    /// Something else. Holds the first row as it is.
+    Unknown(String),
+}
 
-    let t_is_testcases = lines
-        .iter()
-        .any(|l| l.to_ascii_lowercase().contains("case"));
+/// Returns the name, the number of rows, where the row lengths come from, and the number of lines
+/// consumed.
+fn parse_jagged_lines(
+    lines: &[String],
+    idx: usize,
+    seen: &HashSet<String>,
+) -> Option<(String, String, JaggedLen, usize)> {
+    let normalize = |l: &str| l.replace("\\cdots", "\\ldots").replace("\\dots", "\\ldots");
+    let single_re = Regex::new(r"^([A-Za-z]+\d*)_(?:\{1\}|1)$").unwrap();
+    let row_re = Regex::new(
+        r"^(?:([A-Za-z]+\d*)_(?:\{1\}|1)\s+)?([A-Za-z]+\d*)_\{1,\s*1\}(?:\s+([A-Za-z]+\d*)_\{1,\s*2\})?\s+\\ldots\s+([A-Za-z]+\d*)_\{1,\s*([A-Za-z]+\d*)_(?:\{1\}|1)\}$",
+    )
+    .unwrap();
 
-    let mut i = 0usize;
-    while i < lines.len() {
-        let ln = &lines[i];
-        if is_case_placeholder_line(ln) || is_query_placeholder_line(ln) || ln.contains("\\vdots") {
-            i += 1;
-            continue;
-        }
+    // `K_1` on its own line is only a prefix if the row follows.
+    let (prefix, row_idx) = match single_re.captures(lines.get(idx)?) {
+        Some(cap) => (Some(cap[1].to_owned()), idx + 1),
+        None => (None, idx),
+    };
+    let first_row = normalize(lines.get(row_idx)?);
+    let cap = row_re.captures(&first_row)?;
+    let base = &cap[2];
+    if cap.get(3).is_some_and(|m| m.as_str() != base) || &cap[4] != base {
+        return None;
+    }
+    let len_sym = &cap[5];
+    let prefix = match (prefix, cap.get(1)) {
+        (Some(_), Some(_)) => return None,
+        (Some(p), None) => Some(p),
+        (None, m) => m.map(|m| m.as_str().to_owned()),
+    };
+    if prefix.as_ref().is_some_and(|p| p != len_sym) {
+        return None;
+    }
 
-        if let Some((name, ty, consumed)) = parse_grid_lines(lines, i, known_h.as_deref()) {
-            needs_chars = true;
-            if seen.insert(name.clone()) {
-                decls.push(format!("{name}: {ty},"));
+    let last_re = Regex::new(&format!(r"^{}_\{{(.+?),\s*1\}}", regex::escape(base))).unwrap();
+    let mut last: Option<(String, usize)> = None;
+    for (j, line) in lines.iter().enumerate().take(row_idx + 8).skip(row_idx + 1) {
+        let line = normalize(line);
+        let line = match &prefix {
+            Some(p) => {
+                let prefix_re = Regex::new(&format!(r"^{}_\S+\s*", regex::escape(p))).unwrap();
+                prefix_re.replace(&line, "").into_owned()
             }
-            i += consumed;
+            None => line,
+        };
+        if line.is_empty() || line.contains("\\vdots") {
             continue;
         }
-        if let Some((name, ty, consumed)) = parse_pair_repeat(lines, i) {
-            if seen.insert(name.clone()) {
-                decls.push(format!("{name}: {ty},"));
-            }
-            i += consumed;
+        match last_re.captures(&line) {
+            Some(c) => last = Some((c[1].to_owned(), j + 1 - idx)),
+            None => break,
+        }
+    }
+    let (last, consumed) = last?;
+
+    let len = if prefix.is_some() {
+        JaggedLen::Prefixed(snake(len_sym))
+    } else if seen.contains(&snake(len_sym)) {
+        JaggedLen::Earlier(snake(len_sym))
+    } else {
+        JaggedLen::Unknown(lines[row_idx].clone())
+    };
+    Some((snake(base), sym_expr(&last), len, consumed))
+}
+
+/// The indices before the first comma of the subscripts in `line` (`1` for `X_1` and `A_{1,j}`).
+fn first_indices(line: &str) -> Vec<String> {
+    let re = Regex::new(r"[A-Za-z]+\d*_(?:\{([^,{}]+)|([A-Za-z0-9]))").unwrap();
+    re.captures_iter(line)
+        .map(|c| {
+            c.get(1)
+                .or_else(|| c.get(2))
+                .unwrap()
+                .as_str()
+                .trim()
+                .to_owned()
+        })
+        .collect()
+}
+
+/// `X_1` -> `X`, `A_{1,1} \ldots A_{1,K_1}` -> `A_{1} \ldots A_{K}`.
+fn drop_first_index(line: &str) -> String {
+    let scalar_re = Regex::new(r"^([A-Za-z]+\d*)_(?:\{1\}|1)$").unwrap();
+    let row_re = Regex::new(r"^([A-Za-z]+\d*)_\{1,\s*(.+)\}$").unwrap();
+    let inner_re = Regex::new(r"([A-Za-z]+\d*)_(?:\{1\}|1)\b").unwrap();
+    line.split_whitespace()
+        .map(|tok| {
+            if let Some(cap) = scalar_re.captures(tok) {
+                cap[1].to_owned()
+            } else if let Some(cap) = row_re.captures(tok) {
+                format!("{}_{{{}}}", &cap[1], inner_re.replace_all(&cap[2], "$1"))
+            } else {
+                tok.to_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Finds a block of several lines repeated for `i = 1, 2, .., N`, such as `X_i Y_i` followed by
+/// `A_{i,1} \ldots A_{i,M}`. Returns `N`, the block without the `i`, and the number of lines
+/// consumed.
+fn parse_block_repeat(lines: &[String], idx: usize) -> Option<(String, Vec<String>, usize)> {
+    let is_nth = |line: &str, nth: &str| {
+        let indices = first_indices(line);
+        !indices.is_empty() && indices.iter().all(|i| i == nth)
+    };
+    let len = lines[idx..].iter().take_while(|l| is_nth(l, "1")).count();
+    if len < 2 {
+        return None;
+    }
+
+    // Only the first and the last blocks are looked at. The second one may be written out too.
+    let mut j = idx + len;
+    while j < lines.len() && !lines[j].contains("\\vdots") {
+        if !is_nth(&lines[j], "2") {
+            return None;
+        }
+        j += 1;
+    }
+    let last = lines.get(j + 1..j + 1 + len)?;
+    let count = first_indices(&last[0]).pop()?;
+    if count.parse::<usize>().is_ok() || !last.iter().all(|l| is_nth(l, &count)) {
+        return None;
+    }
+    let block = lines[idx..idx + len]
+        .iter()
+        .map(|l| drop_first_index(l))
+        .collect();
+    Some((sym_expr(&count), block, j + 1 + len - idx))
+}
+
+fn is_maze_sample(samples: &[String]) -> bool {
+    // Rows of a `.`/`#` grid are the only sample lines that are not numbers.
+    let rows = samples
+        .iter()
+        .flat_map(|s| s.lines())
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.split_whitespace().all(|t| t.parse::<i64>().is_ok()))
+        .collect::<Vec<_>>();
+    !rows.is_empty() && rows.iter().all(|r| r.chars().all(|c| c == '.' || c == '#'))
+}
+
+/// `A_i` shown once, with `1 \le i \le N` in the constraints, as `a` of length `n`.
+fn index_range_array(
+    sym: &str,
+    seen: &HashSet<String>,
+    ctx: &GuessContext<'_>,
+) -> Option<(String, String)> {
+    let re = Regex::new(r"^([A-Za-z]+\d*)_\{?([a-z])\}?$").unwrap();
+    let cap = re.captures(sym)?;
+    let index = ctx.bounds.get(&cap[2])?;
+    let len = index.upper_symbol.as_ref()?;
+    // The length has to be read before the array.
+    (index.lower == Some(1) && seen.contains(len)).then(|| (snake(&cap[1]), len.clone()))
+}
+
+/// What the line parsers may consult besides the format lines themselves.
+struct GuessContext<'a> {
+    options: &'a TemplateOptions,
+    samples: &'a [String],
+    bounds: &'a HashMap<String, Bounds>,
+    statement: &'a str,
+    /// The values of each 1D array in the samples, by its name.
+    sample_values: &'a HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Default)]
+struct GuessedInput {
+    decls: Vec<String>,
+    needs_chars: bool,
+    needs_usize1: bool,
+    /// Statements to put right after the `input!`.
+    after: Vec<String>,
+    /// Names of the `usize` scalars, in the order they are read.
+    scalars: Vec<String>,
+}
+
+/// Rewrites LaTeX decorations the line parsers do not understand.
+fn normalize_line(line: &str) -> String {
+    // `A_{1}\ A_{2}\ \ldots\ A_{N}` -> `A_{1} A_{2} \ldots A_{N}`
+    let mut line = line.to_owned();
+    for space in ["\\qquad", "\\quad", "\\ ", "\\,", "\\;", "\\:"] {
+        line = line.replace(space, " ");
+    }
+    let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    // `\mathrm{Query}_i` -> `Query_i`
+    let wrapper_re =
+        Regex::new(r"\\(?:mathrm|operatorname|text|textrm)\{\s*([A-Za-z0-9_ ]*?)\s*\}").unwrap();
+    let line = wrapper_re.replace_all(&line, "$1");
+
+    // `A^{(1)}_i` -> `A1_i`. Keeping the label in the name keeps `A^{(1)}` and `A^{(2)}` apart.
+    let sup_re =
+        Regex::new(r"([A-Za-z]+)\^(?:\{\(?([A-Za-z0-9]+)\)?\}|\(([A-Za-z0-9]+)\)|([A-Za-z0-9]))")
+            .unwrap();
+    sup_re
+        .replace_all(&line, |cap: &regex::Captures<'_>| {
+            let label = cap
+                .get(2)
+                .or_else(|| cap.get(3))
+                .or_else(|| cap.get(4))
+                .unwrap()
+                .as_str();
+            format!("{}{}", &cap[1], label)
+        })
+        .into_owned()
+}
+
+fn guess_input_from_lines(lines: &[String], ctx: &GuessContext<'_>) -> GuessedInput {
+    let lines = &lines.iter().map(|l| normalize_line(l)).collect::<Vec<_>>();
+    let mut decls: Vec<String> = Vec::new();
+    let mut needs_chars = false;
+    let mut needs_usize1 = false;
+    let mut after: Vec<String> = Vec::new();
+    let mut scalars: Vec<String> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut known_h: Option<String> = None;
+    let mut known_w: Option<String> = None;
+
+    let t_is_testcases = lines
+        .iter()
+        .any(|l| l.to_ascii_lowercase().contains("case"))
+        || says_t_testcases(ctx.statement);
+
+    let mut i = 0usize;
+    while i < lines.len() {
+        let ln = &lines[i];
+        if is_case_placeholder_line(ln) || is_query_placeholder_line(ln) || ln.contains("\\vdots") {
+            i += 1;
             continue;
         }
-        if let Some((name, ty, consumed)) = parse_vertical_scalars(lines, i) {
+
+        // `input!` cannot read these, so they are read in a loop and the rest after it.
+        let read_loop =
+            if let Some((name, rows, len, consumed)) = parse_jagged_lines(lines, i, &seen) {
+                let mut read_loop = vec![format!("let mut {name} = Vec::with_capacity({rows});")];
+                match len {
+                    JaggedLen::Prefixed(k) => {
+                        read_loop.push(format!("for _ in 0..{rows} {{"));
+                        read_loop.push(format!("    input! {{ {k}: usize, row: [usize; {k}] }}"));
+                        read_loop.push(format!("    {name}.push(row);"));
+                    }
+                    JaggedLen::Earlier(k) => {
+                        read_loop.push(format!("for &len in &{k} {{"));
+                        read_loop.push("    input! { row: [usize; len] }".to_string());
+                        read_loop.push(format!("    {name}.push(row);"));
+                    }
+                    JaggedLen::Unknown(row) => {
+                        read_loop.push(format!("for _ in 0..{rows} {{"));
+                        read_loop.push(format!("    // TODO: read a row like `{row}`"));
+                        read_loop.push("    input! { row: [usize; 0] }".to_string());
+                        read_loop.push(format!("    {name}.push(row);"));
+                    }
+                }
+                read_loop.push("}".to_string());
+                Some((read_loop, consumed))
+            } else if let Some((count, block, consumed)) = parse_block_repeat(lines, i) {
+                let body = guess_input_from_lines(&block, ctx);
+                needs_chars |= body.needs_chars;
+                needs_usize1 |= body.needs_usize1;
+                let mut read_loop = vec![format!("for _ in 0..{count} {{")];
+                read_loop.push("    input! {".to_string());
+                read_loop.extend(body.decls.iter().map(|d| format!("        {d}")));
+                read_loop.push("    }".to_string());
+                read_loop.extend(body.after.iter().map(|a| format!("    {a}")));
+                read_loop.push("}".to_string());
+                Some((read_loop, consumed))
+            } else {
+                None
+            };
+        if let Some((read_loop, consumed)) = read_loop {
+            after.extend(read_loop);
+            let rest = guess_input_from_lines(&lines[i + consumed..], ctx);
+            if !rest.decls.is_empty() {
+                after.push("input! {".to_string());
+                after.extend(rest.decls.iter().map(|d| format!("    {d}")));
+                after.push("}".to_string());
+            }
+            after.extend(rest.after);
+            needs_chars |= rest.needs_chars;
+            needs_usize1 |= rest.needs_usize1;
+            scalars.extend(rest.scalars);
+            break;
+        }
+        if let Some((name, h, cells, consumed)) = parse_grid_lines(
+            lines,
+            i,
+            known_h.as_deref(),
+            known_w.as_deref(),
+            ctx.samples,
+        ) {
+            if let GridCells::Numbers(elem) = cells {
+                if seen.insert(name.clone()) {
+                    let w = known_w.as_deref().unwrap_or("w");
+                    decls.push(format!("{name}: [[{elem}; {w}]; {h}],"));
+                }
+                i += consumed;
+                continue;
+            }
+            needs_chars = true;
+            if seen.insert(name.clone()) {
+                decls.push(format!("{name}: [Chars; {h}],"));
+                if cells == GridCells::Digits {
+                    let conv = format!(
+                        "let {name}: Vec<Vec<usize>> = {name}.iter().map(|r| r.iter().map(|&c| \
+                         c.to_digit(10).unwrap() as usize).collect()).collect();"
+                    );
+                    if ctx.options.digit_grid {
+                        after.push(match &known_w {
+                            Some(w) => format!("{conv} // [[usize; {w}]; {h}]"),
+                            None => conv,
+                        });
+                    } else {
+                        after.push(format!("// {conv}"));
+                    }
+                } else if is_maze_sample(ctx.samples) {
+                    let conv = format!(
+                        "let {name}: Vec<Vec<bool>> = \
+                         {name}.iter().map(|r| r.iter().map(|&c| c == '#').collect()).collect();"
+                    );
+                    if ctx.options.bool_grid {
+                        after.push(match &known_w {
+                            Some(w) => format!("{conv} // [[bool; {w}]; {h}]"),
+                            None => conv,
+                        });
+                    } else {
+                        after.push(format!("// {conv}"));
+                    }
+                }
+            }
+            i += consumed;
+            continue;
+        }
+        if let Some((name, ty, consumed)) = parse_matrix_lines(lines, i) {
             if seen.insert(name.clone()) {
                 decls.push(format!("{name}: {ty},"));
             }
             i += consumed;
             continue;
         }
-        if let Some((name, ty)) = parse_1d_array_line(ln) {
+        if let Some((name, ty, consumed)) = parse_pair_repeat(lines, i, ctx) {
+            if seen.insert(name.clone()) {
+                decls.push(format!("{name}: {ty}"));
+            }
+            i += consumed;
+            continue;
+        }
+        if let Some((name, ty, consumed)) = parse_vertical_scalars(lines, i) {
             if seen.insert(name.clone()) {
                 decls.push(format!("{name}: {ty},"));
             }
+            i += consumed;
+            continue;
+        }
+        if let Some((name, ty)) = parse_1d_array_line(ln, ctx) {
+            if seen.insert(name.clone()) {
+                if ctx.options.permutation_usize1 && is_permutation(&name, ctx) {
+                    decls.push(format!("{name}: {ty}, // permutation"));
+                } else {
+                    decls.push(format!("{name}: {ty},"));
+                }
+                needs_usize1 |= ty.contains("Usize1");
+                needs_chars |= ty.contains("Chars");
+                if is_one_indexed(&name, ctx) {
+                    match ctx.options.one_indexed {
+                        OneIndexed::Keep | OneIndexed::Usize1 => {}
+                        OneIndexed::Annotate => {
+                            after.push(format!("// NOTE: {name}_i are 1-indexed"));
+                        }
+                        OneIndexed::Convert => after.push(format!(
+                            "let {name} = {name}.iter().map(|&x| x - 1).collect::<Vec<_>>();"
+                        )),
+                    }
+                }
+            }
             i += 1;
             continue;
         }
@@ -331,10 +1548,14 @@ fn guess_input_from_lines(lines: &[String]) -> (Vec<String>, bool) {
                 let name = snake(tok);
                 if seen.insert(name.clone()) {
                     decls.push(format!("{name}: usize,"));
+                    scalars.push(name.clone());
                 }
                 if name == "h" {
                     known_h = Some("h".to_string());
                 }
+                if name == "w" {
+                    known_w = Some("w".to_string());
+                }
             }
             i += 1;
             continue;
@@ -347,17 +1568,38 @@ fn guess_input_from_lines(lines: &[String]) -> (Vec<String>, bool) {
             && !ln.contains("\\dots")
         {
             let sym = ln.trim();
+            if let Some((name, len)) = index_range_array(sym, &seen, ctx) {
+                if seen.insert(name.clone()) {
+                    decls.push(format!("{name}: [usize; {len}],"));
+                }
+                i += 1;
+                continue;
+            }
             let name = snake(sym);
             let ty = if sym.eq_ignore_ascii_case("T") && t_is_testcases {
                 "usize".to_string()
-            } else if is_string_symbol(sym) {
+            } else if statement_says_string(sym, ctx).unwrap_or_else(|| is_string_symbol(sym)) {
                 needs_chars = true;
                 "Chars".to_string()
             } else {
                 "usize".to_string()
             };
             if seen.insert(name.clone()) {
-                decls.push(format!("{name}: {ty},"));
+                match string_length(sym, ctx) {
+                    Some(len) if ty == "Chars" => {
+                        decls.push(format!("{name}: {ty}, // |{name}| {len}"));
+                    }
+                    _ => decls.push(format!("{name}: {ty},")),
+                }
+                if ty == "usize" {
+                    // Like the `h` and `w` of `H W`, but each on its own line.
+                    match &*name {
+                        "h" => known_h = Some(name.clone()),
+                        "w" => known_w = Some(name.clone()),
+                        _ => {}
+                    }
+                    scalars.push(name);
+                }
             }
             i += 1;
             continue;
@@ -367,138 +1609,3262 @@ fn guess_input_from_lines(lines: &[String]) -> (Vec<String>, bool) {
         i += 1;
     }
 
-    (decls, needs_chars)
+    GuessedInput {
+        decls,
+        needs_chars,
+        needs_usize1,
+        after,
+        scalars,
+    }
 }
 
-fn render_section(task: &TaskSection) -> anyhow::Result<String> {
-    let all_lines: Vec<String> = task.input_blocks.iter().flatten().cloned().collect();
-    let has_cases = all_lines.iter().any(|l| is_case_placeholder_line(l));
-    let has_queries = all_lines.iter().any(|l| is_query_placeholder_line(l));
+fn looks_like_format_block(block: &[String]) -> bool {
+    block.iter().any(|l| {
+        l.contains('_')
+            || l.contains("\\ldots")
+            || l.contains("\\cdots")
+            || l.contains("\\dots")
+            || l.split_whitespace()
+                .all(|t| t.len() == 1 && t.chars().all(|c| c.is_ascii_uppercase()))
+    })
+}
 
-    let first = task
-        .input_blocks
-        .first()
-        .with_context(|| format!("{}: missing input format <pre>", task.letter))?;
-    let (decls, needs_chars) = guess_input_from_lines(first);
-    let mut out: Vec<String> = Vec::new();
-    if needs_chars {
-        out.push("use proconio::{input, marker::Chars};".to_string());
+/// Picks the `<pre>` that most likely holds the input format, together with the other plausible
+/// candidates.
+fn select_format_block(blocks: &[Vec<String>]) -> Option<(usize, Vec<usize>)> {
+    let candidates = (0..blocks.len())
+        .filter(|&i| looks_like_format_block(&blocks[i]))
+        .collect::<Vec<_>>();
+    // The richest block wins. Ties go to the earlier one.
+    let primary = match candidates
+        .iter()
+        .copied()
+        .max_by_key(|&i| (blocks[i].len(), Reverse(i)))
+    {
+        Some(primary) => primary,
+        None => {
+            return blocks
+                .iter()
+                .position(|b| !b.is_empty())
+                .map(|i| (i, vec![]))
+        }
+    };
+    let alternatives = candidates.into_iter().filter(|&i| i != primary).collect();
+    Some((primary, alternatives))
+}
+
+fn wrap_in_module(letter: &str, lines: Vec<String>) -> Vec<String> {
+    let mut out = vec![format!("mod {} {{", snake(letter))];
+    for line in lines {
+        let line = if line == "fn main() {" {
+            "pub fn main() {".to_string()
+        } else {
+            line
+        };
+        if line.is_empty() {
+            out.push(line);
+        } else {
+            out.push(format!("    {line}"));
+        }
+    }
+    out.push("}".to_string());
+    out
+}
+
+fn proconio_use_line(needs_chars: bool, needs_usize1: bool) -> String {
+    match (needs_chars, needs_usize1) {
+        (false, false) => "use proconio::input;".to_string(),
+        (true, false) => "use proconio::{input, marker::Chars};".to_string(),
+        (false, true) => "use proconio::{input, marker::Usize1};".to_string(),
+        (true, true) => "use proconio::{input, marker::{Chars, Usize1}};".to_string(),
+    }
+}
+
+/// Adds `item` to a `use proconio::..;` line.
+fn add_proconio_import(use_line: &str, item: &str) -> String {
+    let items = use_line
+        .trim_start_matches("use proconio::")
+        .trim_end_matches(';')
+        .trim_start_matches('{')
+        .trim_end_matches('}');
+    let mut items = items.split(", ").collect::<Vec<_>>();
+    if !items.contains(&item) {
+        items.push(item);
+    }
+    if items.len() == 1 {
+        format!("use proconio::{};", items[0])
     } else {
-        out.push("use proconio::input;".to_string());
+        format!("use proconio::{{{}}};", items.join(", "))
     }
-    out.push("fn main() {".to_string());
+}
 
-    if !has_cases && !has_queries {
-        out.push("    input! {".to_string());
-        for d in decls {
-            out.push(format!("        {d}"));
+/// A tokenizer over stdin to read with instead of proconio.
+const MANUAL_SCANNER: &[&str] = &[
+    "#[derive(Default)]",
+    "struct Scanner {",
+    "    tokens: Vec<String>,",
+    "}",
+    "",
+    "impl Scanner {",
+    "    fn next<T: std::str::FromStr>(&mut self) -> T",
+    "    where",
+    "        T::Err: std::fmt::Debug,",
+    "    {",
+    "        loop {",
+    "            if let Some(token) = self.tokens.pop() {",
+    "                return token.parse().unwrap();",
+    "            }",
+    "            let mut line = String::new();",
+    "            assert!(std::io::stdin().read_line(&mut line).unwrap() > 0, \"unexpected EOF\");",
+    "            self.tokens = line.split_whitespace().rev().map(ToOwned::to_owned).collect();",
+    "        }",
+    "    }",
+    "}",
+];
+
+/// Replaces every `input!` with `let`s reading from a `Scanner`, declared at the top of `main`
+/// and defined at the end.
+fn to_manual_reader(lines: Vec<String>) -> Vec<String> {
+    // `Fallback::ReadToString` reads stdin by itself.
+    if !lines.iter().any(|l| l.starts_with("use proconio::")) {
+        return lines;
+    }
+    let inline_re = Regex::new(r"input! \{ (.*?) \}").unwrap();
+    let mut out = Vec::with_capacity(lines.len());
+    let mut in_input = false;
+    for line in lines {
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let trimmed = line.trim();
+        if in_input {
+            if trimmed == "}" {
+                in_input = false;
+            } else {
+                let indent = indent.strip_suffix("    ").unwrap_or(indent);
+                let (decl, comment) = match trimmed.find("//") {
+                    Some(i) => (trimmed[..i].trim(), Some(&trimmed[i..])),
+                    None => (trimmed, None),
+                };
+                let stmt = match (manual_read(decl.trim_end_matches(',')), comment) {
+                    (_, Some(comment)) if decl.is_empty() => comment.to_owned(),
+                    (stmt, Some(comment)) => format!("{stmt} {comment}"),
+                    (stmt, None) => stmt,
+                };
+                out.push(format!("{indent}{stmt}"));
+            }
+        } else if trimmed.starts_with("use proconio::") {
+            // Nothing of proconio is left.
+        } else if trimmed == "input! {" {
+            in_input = true;
+        } else if trimmed.ends_with("fn main() {") {
+            out.push(line.clone());
+            out.push(format!("{indent}    let mut sc = Scanner::default();"));
+        } else {
+            out.push(
+                inline_re
+                    .replace_all(&line, |cap: &regex::Captures<'_>| {
+                        split_top_level(&cap[1], ',')
+                            .into_iter()
+                            .map(manual_read)
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .into_owned(),
+            );
         }
-        out.push("    }".to_string());
-        out.push("}".to_string());
-        return Ok(out.join("\n"));
     }
+    out.push(String::new());
+    out.extend(MANUAL_SCANNER.iter().map(|&l| l.to_owned()));
+    out
+}
 
-    // Header
-    out.push("    input! {".to_string());
-    for d in decls {
-        out.push(format!("        {d}"));
+/// The `let` reading one `input!` declaration such as `a: [usize; n]`. Anything else (a
+/// `/* TODO */`) is kept as it is.
+fn manual_read(decl: &str) -> String {
+    let decl_re = Regex::new(r"^((?:mut\s+)?[a-z_][a-z0-9_]*)\s*:\s*(.+)$").unwrap();
+    match decl_re.captures(decl.trim()) {
+        Some(cap) => format!("let {} = {};", &cap[1], manual_expr(&cap[2])),
+        None => decl.trim().to_owned(),
     }
-    out.push("    }".to_string());
+}
 
-    if has_cases {
-        if task.input_blocks.len() >= 2 {
-            let (case_decls, case_needs_chars) = guess_input_from_lines(&task.input_blocks[1]);
-            if case_needs_chars && !needs_chars {
-                out[0] = "use proconio::{input, marker::Chars};".to_string();
-            }
-            out.push("    for _ in 0..t {".to_string());
-            out.push("        input! {".to_string());
-            for d in case_decls {
-                out.push(format!("            {d}"));
+/// The expression reading a value of the `input!` type `ty` from `sc`.
+fn manual_expr(ty: &str) -> String {
+    let ty = ty.trim();
+    if let Some(inner) = ty.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        return match &split_top_level(inner, ';')[..] {
+            [elem, len] => format!(
+                "(0..{}).map(|_| {}).collect::<Vec<_>>()",
+                len.trim(),
+                manual_expr(elem),
+            ),
+            // Prefixed with its length.
+            _ => format!(
+                "{{ let len = sc.next::<usize>(); (0..len).map(|_| {}).collect::<Vec<_>>() }}",
+                manual_expr(inner),
+            ),
+        };
+    }
+    if let Some(inner) = ty.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        let elems = split_top_level(inner, ',')
+            .into_iter()
+            .map(manual_expr)
+            .collect::<Vec<_>>();
+        return format!("({})", elems.join(", "));
+    }
+    match ty {
+        "Chars" => "sc.next::<String>().chars().collect::<Vec<_>>()".to_owned(),
+        "Bytes" => "sc.next::<String>().into_bytes()".to_owned(),
+        "Usize1" => "sc.next::<usize>() - 1".to_owned(),
+        "Isize1" => "sc.next::<isize>() - 1".to_owned(),
+        ty => format!("sc.next::<{ty}>()"),
+    }
+}
+
+/// Splits `s` at the `sep`s outside brackets.
+fn split_top_level(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            c if c == sep && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
             }
-            out.push("        }".to_string());
-            out.push("        /* TODO: solve testcase */".to_string());
-            out.push("    }".to_string());
-            out.push("}".to_string());
-            return Ok(out.join("\n"));
+            _ => {}
         }
-        out.push("    for _ in 0..t {".to_string());
-        out.push("        input! { /* TODO: per-testcase fields */ }".to_string());
-        out.push("        /* TODO: solve testcase */".to_string());
-        out.push("    }".to_string());
-        out.push("}".to_string());
-        return Ok(out.join("\n"));
     }
+    parts.push(&s[start..]);
+    parts.retain(|p| !p.trim().is_empty());
+    parts
+}
 
-    // Queries
-    out.push("    for _ in 0..q {".to_string());
-    out.push("        input! { qt: usize }".to_string());
-    let mut qtypes: Vec<(i32, Vec<String>)> = Vec::new();
-    for b in task.input_blocks.iter().skip(1) {
-        if b.len() != 1 {
-            continue;
+/// Puts `#[fastout]` on `main`, importing it along with the others from proconio.
+fn add_fastout(lines: Vec<String>) -> Vec<String> {
+    if !lines.iter().any(|l| l.starts_with("use proconio::")) {
+        return lines;
+    }
+    let mut out = Vec::with_capacity(lines.len() + 1);
+    for line in lines {
+        if let Some(items) = line
+            .strip_prefix("use proconio::")
+            .and_then(|l| l.strip_suffix(';'))
+        {
+            // Where rustfmt would sort it to.
+            out.push(match items.strip_prefix('{') {
+                Some(items) => format!("use proconio::{{fastout, {items};"),
+                None => format!("use proconio::{{fastout, {items}}};"),
+            });
+        } else if line == "fn main() {" {
+            out.push("#[fastout]".to_owned());
+            out.push(line);
+        } else {
+            out.push(line);
         }
-        let toks: Vec<&str> = b[0].split_whitespace().collect();
-        if toks.is_empty() {
-            continue;
+    }
+    out
+}
+
+/// Makes every `input!` read from one `source` declared at the top of `main`.
+fn read_from_source(lines: Vec<String>, input_source: InputSource, letter: &str) -> Vec<String> {
+    let (item, std_use, init) = match input_source {
+        InputSource::Stdin => return lines,
+        InputSource::ReadAll => (
+            "source::once::OnceSource",
+            "use std::io::Read as _;",
+            vec![
+                "let mut buf = String::new();".to_owned(),
+                "std::io::stdin().read_to_string(&mut buf).unwrap();".to_owned(),
+                "let mut source = OnceSource::from(buf.as_str());".to_owned(),
+            ],
+        ),
+        InputSource::Line => (
+            "source::line::LineSource",
+            "use std::io::{stdin, BufReader};",
+            vec!["let mut source = LineSource::new(BufReader::new(stdin().lock()));".to_owned()],
+        ),
+        InputSource::SampleFile => (
+            "source::once::OnceSource",
+            "use std::io::{stdin, BufReader};",
+            vec![
+                "#[cfg(debug_assertions)]".to_owned(),
+                format!(
+                    "let mut source = OnceSource::new(BufReader::new(\
+                     std::fs::File::open(concat!(env!(\"CARGO_MANIFEST_DIR\"), \
+                     \"/tests/{}/1.in\")).unwrap()));",
+                    letter.to_lowercase(),
+                ),
+                "#[cfg(not(debug_assertions))]".to_owned(),
+                "let mut source = OnceSource::new(BufReader::new(stdin()));".to_owned(),
+            ],
+        ),
+    };
+    // `Fallback::ReadToString` reads stdin by itself.
+    if !lines.iter().any(|l| l.starts_with("use proconio::")) {
+        return lines;
+    }
+    let mut out = Vec::with_capacity(lines.len());
+    for line in lines {
+        if line.starts_with("use proconio::") {
+            out.push(add_proconio_import(&line, item));
+            out.push(std_use.to_string());
+        } else if line.ends_with("fn main() {") {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let init = init.iter().map(|l| format!("{indent}    {l}"));
+            out.push(line.clone());
+            out.extend(init);
+        } else if line.trim_start() == "input! {" {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let from = format!("{indent}    from &mut source,");
+            out.push(line);
+            out.push(from);
+        } else {
+            out.push(line.replace("input! { ", "input! { from &mut source, "));
         }
-        let qt = toks[0].parse::<i32>().ok();
-        if qt.is_none() {
-            continue;
+    }
+    out
+}
+
+fn render_section(task: &TaskSection, options: &TemplateOptions) -> anyhow::Result<String> {
+    let mut out = render_main(task, options)?;
+    if options.suppress_unused {
+        out = reference_bindings(out);
+    }
+    if options.yes_no {
+        if let Some((yes, no)) = yes_no_words(&task.output) {
+            out = add_yes_no(out, yes, no);
         }
-        let rest = toks[1..].iter().map(|s| s.to_string()).collect();
-        qtypes.push((qt.unwrap(), rest));
     }
-    qtypes.sort_by_key(|x| x.0);
-    if !qtypes.is_empty() {
-        out.push("        match qt {".to_string());
-        for (qt, toks) in qtypes {
-            if toks.is_empty() {
-                out.push(format!("            {qt} => {{}},"));
-            } else {
-                let inner = toks
-                    .iter()
-                    .map(|t| format!("{}: usize", snake(t)))
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                out.push(format!("            {qt} => {{ input! {{ {inner} }} }},"));
+    if options.print_array && prints_array(&task.output) {
+        out = add_print_array(out);
+    }
+    if prints_answer_per_line(&task.output) {
+        out = add_case_answers(out, options.batch_output);
+    }
+    out = comment_out_later_todos(out);
+    if let Some(modulus) = &task.modulus {
+        let main = out
+            .iter()
+            .position(|l| l == "fn main() {")
+            .unwrap_or(out.len());
+        let lines = match modulus {
+            Modulus::Fixed(modulus) => vec![
+                "// NOTE: print the answer reduced modulo `MOD`.".to_string(),
+                format!("const MOD: u64 = {modulus};"),
+            ],
+            // It is already read as a `usize`.
+            Modulus::Input(name) => vec![format!(
+                "// NOTE: modulus read as `{name}`. Print the answer reduced modulo it."
+            )],
+        };
+        out.splice(main..main, lines);
+    }
+    if let Some(vertices) = deep_graph_vertices(task, &out, options) {
+        let main = out
+            .iter()
+            .position(|l| l == "fn main() {")
+            .unwrap_or(out.len());
+        out.insert(
+            main,
+            format!(
+                "// NOTE: up to {vertices} vertices. A recursive DFS may overflow the stack, so use \
+                 an explicit stack or run it in a thread with a larger stack."
+            ),
+        );
+    }
+    out = match options.reader {
+        Reader::Proconio => read_from_source(out, options.input_source, &task.letter),
+        Reader::Manual => to_manual_reader(out),
+    };
+    if options.fastout && options.reader == Reader::Proconio {
+        out = add_fastout(out);
+    }
+    if options.wrap_in_module {
+        out = wrap_in_module(&task.letter, out);
+    }
+    if let Some(version) = options.proconio_version {
+        out = target_proconio_version(out, version);
+    }
+    if options.allow_clippy {
+        out.insert(0, "#![allow(clippy::all)]".to_string());
+    }
+    if let (Some(contest), Some(slug)) = (&options.contest, options.task_slugs.get(&task.letter)) {
+        out.insert(
+            0,
+            format!("// https://atcoder.jp/contests/{contest}/tasks/{slug}"),
+        );
+    }
+    let out = out.join("\n");
+    if options.validate_syntax {
+        validate_syntax(&out)?;
+    }
+    Ok(out)
+}
+
+/// Fails if `content` does not parse as a Rust file. The macros such as `input!` are not expanded.
+#[cfg(feature = "validate-syntax")]
+fn validate_syntax(content: &str) -> anyhow::Result<()> {
+    syn::parse_file(content)
+        .map(drop)
+        .with_context(|| "the generated code does not parse")
+}
+
+#[cfg(not(feature = "validate-syntax"))]
+fn validate_syntax(_: &str) -> anyhow::Result<()> {
+    Ok(())
+}
+
+/// The upper bound of the vertex count, if `lines` read the edges of a tree or graph that may be
+/// too deep for a recursive DFS.
+fn deep_graph_vertices(
+    task: &TaskSection,
+    lines: &[String],
+    options: &TemplateOptions,
+) -> Option<i128> {
+    let graph_re = Regex::new(r"(?i)木|グラフ|\btree\b|\bgraph\b").unwrap();
+    let edges_re = Regex::new(
+        r"^\s*[a-z_][a-z0-9_]*: \[\((?:usize|Usize1), (?:usize|Usize1)(?:, [A-Za-z0-9]+)?\); ([a-z_][a-z0-9_]*)(?:-1)?\],(?:\s*//.*)?$",
+    )
+    .unwrap();
+    if !graph_re.is_match(&task.statement) {
+        return None;
+    }
+    let len_sym = lines
+        .iter()
+        .find_map(|l| edges_re.captures(l).map(|cap| cap[1].to_owned()))?;
+    // The vertices are usually `N` even when the edges are `M`.
+    let bounds = parse_constraints(&task.constraints);
+    let vertices = ["n", &*len_sym]
+        .iter()
+        .find_map(|sym| bounds.get(*sym).and_then(|b| b.upper))?;
+    let threshold = options
+        .deep_recursion_threshold
+        .unwrap_or(DEEP_RECURSION_THRESHOLD);
+    (vertices >= i128::from(threshold)).then_some(vertices)
+}
+
+/// Finds the two words the output section asks to print, as `Yes`/`No` or `YES`/`NO`.
+fn yes_no_words(output: &str) -> Option<(&'static str, &'static str)> {
+    [("Yes", "No"), ("YES", "NO")]
+        .into_iter()
+        .find(|(yes, no)| {
+            [yes, no].iter().all(|word| {
+                Regex::new(&format!(r"\b{word}\b"))
+                    .unwrap()
+                    .is_match(output)
+            })
+        })
+}
+
+/// Calls `yn` at the end of `main`, and defines it after `main`.
+fn add_yes_no(lines: Vec<String>, yes: &str, no: &str) -> Vec<String> {
+    add_helper(
+        lines,
+        "yn(todo!()); // TODO: decide the answer",
+        vec![
+            "fn yn(b: bool) {".to_owned(),
+            format!(r#"    println!("{{}}", if b {{ "{yes}" }} else {{ "{no}" }});"#),
+            "}".to_owned(),
+        ],
+    )
+}
+
+/// Whether the output section asks for a sequence like `B_1 B_2 \ldots B_N`.
+fn prints_array(output: &str) -> bool {
+    let re = Regex::new(r"[A-Za-z]+_\{?[0-9a-z]+\}?,?\s*(?:\\[lc]?dots|\.\.\.|…)").unwrap();
+    re.is_match(output)
+}
+
+/// Whether the output section asks for the answer of each testcase on its own line.
+fn prints_answer_per_line(output: &str) -> bool {
+    let re = Regex::new(
+        r"(?i)\beach\s+(?:test\s*)?case|on\s+(?:its|their)\s+own\s+lines?|\bone\s+per\s+line|\bT\s+lines\b|\bT\s*行|各テストケース|テストケースごと|改行区切り",
+    )
+    .unwrap();
+    re.is_match(output)
+}
+
+/// Prints the answer of each testcase after `/* TODO: solve testcase */`, through one
+/// `BufWriter` flushed at the end of `main` if `batch`.
+fn add_case_answers(mut lines: Vec<String>, batch: bool) -> Vec<String> {
+    let Some(solve) = lines
+        .iter()
+        .position(|l| l.trim() == "/* TODO: solve testcase */")
+    else {
+        return lines;
+    };
+    let indent = lines[solve][..lines[solve].len() - lines[solve].trim_start().len()].to_owned();
+    let print = if batch {
+        r#"writeln!(out, "{ans}").unwrap();"#
+    } else {
+        r#"println!("{ans}");"#
+    };
+    lines.splice(
+        solve + 1..solve + 1,
+        [
+            format!("{indent}let ans: usize = todo!(); // TODO: the answer of the testcase"),
+            format!("{indent}{print}"),
+        ],
+    );
+    if !batch {
+        return lines;
+    }
+    let Some(main) = lines.iter().position(|l| l == "fn main() {") else {
+        return lines;
+    };
+    if let Some(end) = lines[main..].iter().position(|l| l == "}") {
+        lines.insert(main + end, "    out.flush().unwrap();".to_owned());
+    }
+    lines.insert(
+        main + 1,
+        "    let mut out = BufWriter::new(std::io::stdout().lock());".to_owned(),
+    );
+    let uses = lines
+        .iter()
+        .position(|l| l.starts_with("use proconio::"))
+        .map_or(0, |i| i + 1);
+    lines.insert(uses, "use std::io::{BufWriter, Write as _};".to_owned());
+    lines
+}
+
+fn add_print_array(lines: Vec<String>) -> Vec<String> {
+    add_helper(
+        lines,
+        "print_array::<usize>(todo!()); // TODO: the answer",
+        vec![
+            "fn print_array<T: std::fmt::Display>(a: &[T]) {".to_owned(),
+            "    let a = a.iter().map(|x| x.to_string()).collect::<Vec<_>>();".to_owned(),
+            r#"    println!("{}", a.join(" "));"#.to_owned(),
+            "}".to_owned(),
+        ],
+    )
+}
+
+/// Comments out the calls with `todo!()` after the first one, since the first one makes them
+/// unreachable, and allows the helpers they call to stay unused.
+fn comment_out_later_todos(lines: Vec<String>) -> Vec<String> {
+    let mut first = true;
+    let mut unused = vec![];
+    let mut out = Vec::with_capacity(lines.len());
+    for line in lines {
+        if !line.contains("todo!()") || line.trim_start().starts_with("//") {
+            out.push(line);
+        } else if first {
+            first = false;
+            out.push(line);
+        } else {
+            let (indent, call) = line.split_at(line.len() - line.trim_start().len());
+            if let Some(name) = call.split(['(', ':', '<']).next() {
+                unused.push(name.to_owned());
             }
+            out.push(format!("{indent}// {call}"));
         }
-        out.push("            _ => unreachable!(),".to_string());
-        out.push("        }".to_string());
-    } else {
-        out.push("        /* TODO: per-query fields */".to_string());
     }
-    out.push("        /* TODO: process query */".to_string());
-    out.push("    }".to_string());
-    out.push("}".to_string());
-    Ok(out.join("\n"))
+    let mut i = 0;
+    while i < out.len() {
+        let is_unused = unused.iter().any(|name| {
+            out[i]
+                .strip_prefix("fn ")
+                .and_then(|l| l.strip_prefix(&**name))
+                .is_some_and(|l| l.starts_with(['(', '<']))
+        });
+        if is_unused {
+            out.insert(i, "#[allow(dead_code)]".to_owned());
+            i += 1;
+        }
+        i += 1;
+    }
+    out
 }
 
-pub(crate) fn generate_template(
-    dest_dir: &Utf8Path,
-    shell: &mut Shell,
-) -> anyhow::Result<Option<HashMap<Utf8PathBuf, String>>> {
-    let task_path = dest_dir.join("task.html");
-    if !task_path.exists() {
-        return Ok(None);
+/// Calls a helper at the end of `main`, and defines it after everything.
+fn add_helper(mut lines: Vec<String>, call: &str, helper: Vec<String>) -> Vec<String> {
+    let Some(main) = lines.iter().position(|l| l == "fn main() {") else {
+        return lines;
+    };
+    let Some(end) = lines[main..].iter().position(|l| l == "}") else {
+        return lines;
+    };
+    lines.insert(main + end, format!("    {call}"));
+    lines.push(String::new());
+    lines.extend(helper);
+    lines
+}
+
+/// References the variables read in `main` at its end, and the ones read in a loop right after
+/// the `input!`, since the loop body may move them.
+fn reference_bindings(lines: Vec<String>) -> Vec<String> {
+    enum Block {
+        Main(Vec<String>),
+        Loop,
+        Other,
     }
-    let html = fs::read_to_string(&task_path).with_context(|| format!("failed to read {task_path}"))?;
-    let sections = parse_task_sections(&html);
-    let src_dir = dest_dir.join("src").join("bin");
-    let mut out: HashMap<Utf8PathBuf, String> = HashMap::new();
-    for task in &sections {
-        let src_path = src_dir
-            .join(task.letter.to_kebab_case())
-            .with_extension("rs");
-        match render_section(task) {
-            Ok(content) => {
-                out.insert(src_path, content);
-            }
-            Err(err) => {
-                shell.warn(format!("render_section failed at {}: {err}", task.letter))?;
+
+    fn reference(indent: &str, names: &[String]) -> Option<String> {
+        match names {
+            [] => None,
+            [name] => Some(format!("{indent}let _ = &{name};")),
+            names => {
+                let refs = names.iter().map(|n| format!("&{n}")).collect::<Vec<_>>();
+                Some(format!("{indent}let _ = ({});", refs.join(", ")))
             }
         }
     }
-    Ok(Some(out))
+
+    let decl_re = Regex::new(r"(?:^|,\s*)(?:mut\s+)?([a-z_][a-z0-9_]*)\s*:").unwrap();
+    let let_re = Regex::new(r"^let\s+(?:mut\s+)?([a-z_][a-z0-9_]*)\b").unwrap();
+
+    let mut out = Vec::with_capacity(lines.len());
+    let mut blocks: Vec<Block> = vec![];
+    // The names in the `input! {` being read, if any.
+    let mut input: Option<Vec<String>> = None;
+    for line in lines {
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let trimmed = line.trim();
+        let mut read = None;
+        let mut closing = None;
+        if let Some(names) = &mut input {
+            if trimmed == "}" {
+                read = input.take();
+            } else if let Some(cap) = decl_re.captures(trimmed) {
+                names.push(cap[1].to_owned());
+            }
+        } else if trimmed == "input! {" {
+            input = Some(vec![]);
+        } else if let Some(inner) = trimmed
+            .strip_prefix("input! {")
+            .and_then(|l| l.strip_suffix('}'))
+        {
+            read = Some(
+                decl_re
+                    .captures_iter(inner.trim())
+                    .map(|c| c[1].to_owned())
+                    .collect(),
+            );
+        } else if trimmed.ends_with('{') {
+            blocks.push(if trimmed == "fn main() {" {
+                Block::Main(vec![])
+            } else if trimmed.starts_with("for ") {
+                Block::Loop
+            } else {
+                Block::Other
+            });
+        } else if trimmed == "}" || trimmed == "}," {
+            closing = blocks.pop();
+        } else if let (Some(cap), Some(Block::Main(names))) =
+            (let_re.captures(trimmed), blocks.last_mut())
+        {
+            if &cap[1] != "_" {
+                names.push(cap[1].to_owned());
+            }
+        }
+
+        if let Some(Block::Main(names)) = closing {
+            let mut seen = HashSet::new();
+            let names = names
+                .into_iter()
+                .filter(|n| seen.insert(n.clone()))
+                .collect::<Vec<_>>();
+            out.extend(reference(&format!("{indent}    "), &names));
+        }
+        out.push(line.clone());
+        if let Some(names) = read {
+            match blocks.last_mut() {
+                Some(Block::Main(main)) => main.extend(names),
+                Some(Block::Loop) => out.extend(reference(indent, &names)),
+                _ => {}
+            }
+        }
+    }
+    out
+}
+
+/// Notes the targeted proconio above its `use`. The markers have stayed in `proconio::marker`
+/// since 0.3, so the `use` itself is kept.
+fn target_proconio_version(lines: Vec<String>, version: ProconioVersion) -> Vec<String> {
+    let mut out = Vec::with_capacity(lines.len() + 1);
+    for line in lines {
+        if line.trim_start().starts_with("use proconio::") {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            out.push(format!(
+                "{indent}// proconio = \"{}\"",
+                version.requirement(),
+            ));
+        }
+        out.push(line);
+    }
+    out
+}
+
+const UNRECOGNIZED_NOTE: &str = "// TODO: parse manually. The input format is:";
+
+/// The template for a format none of whose lines are understood.
+fn render_unrecognized(lines: &[String], fallback: Fallback) -> Vec<String> {
+    let mut out = match fallback {
+        Fallback::Input => vec![
+            "use proconio::input;".to_string(),
+            "fn main() {".to_string(),
+        ],
+        Fallback::ReadToString => vec![
+            "use std::io::Read as _;".to_string(),
+            "fn main() {".to_string(),
+            "    let mut input = String::new();".to_string(),
+            "    std::io::stdin().read_to_string(&mut input).unwrap();".to_string(),
+        ],
+    };
+    out.push(format!("    {UNRECOGNIZED_NOTE}"));
+    out.extend(lines.iter().map(|l| format!("    //   {l}")));
+    if fallback == Fallback::Input {
+        out.push("    input! {".to_string());
+        out.push("    }".to_string());
+    }
+    out.push("}".to_string());
+    out
+}
+
+fn render_main(task: &TaskSection, options: &TemplateOptions) -> anyhow::Result<Vec<String>> {
+    let bounds = parse_constraints(&task.constraints);
+    let sample_values = task
+        .input_blocks
+        .first()
+        .map(|lines| sample_array_values(lines, &task.samples))
+        .unwrap_or_default();
+    let ctx = GuessContext {
+        options,
+        samples: &task.samples,
+        bounds: &bounds,
+        statement: &task.statement,
+        sample_values: &sample_values,
+    };
+    if task.input_is_image {
+        return Ok(vec![
+            "use proconio::input;".to_string(),
+            "fn main() {".to_string(),
+            "    // NOTE: the input format is only given as an image.".to_string(),
+            "    input! {".to_string(),
+            "        /* TODO: fill in by hand */".to_string(),
+            "    }".to_string(),
+            "}".to_string(),
+        ]);
+    }
+
+    let all_lines: Vec<String> = task.input_blocks.iter().flatten().cloned().collect();
+    let has_cases =
+        all_lines.iter().any(|l| is_case_placeholder_line(l)) || has_cases_in_prose(task);
+    let has_queries = all_lines.iter().any(|l| is_query_placeholder_line(l));
+
+    if !has_cases && !has_queries {
+        if let Some(case_lines) = inline_case_lines(task) {
+            let case = guess_input_from_lines(case_lines, &ctx);
+            let mut out = vec![
+                proconio_use_line(case.needs_chars, case.needs_usize1),
+                "fn main() {".to_string(),
+            ];
+            if options.format_comment {
+                out.extend(format_comment(&task.input_blocks[0]));
+            }
+            out.push("    input! {".to_string());
+            out.push("        t: usize,".to_string());
+            out.push("    }".to_string());
+            push_case_loop(&mut out, case);
+            out.push("}".to_string());
+            return Ok(out);
+        }
+    }
+
+    // The case/query layouts are positional, so only the base layout may pick another block.
+    let (primary, alternatives) = if has_cases || has_queries {
+        (0, vec![])
+    } else {
+        select_format_block(&task.input_blocks).unwrap_or((0, vec![]))
+    };
+    let first = task
+        .input_blocks
+        .get(primary)
+        .with_context(|| format!("{}: missing input format <pre>", task.letter))?;
+    let GuessedInput {
+        decls,
+        needs_chars,
+        needs_usize1,
+        after,
+        scalars,
+    } = guess_input_from_lines(first, &ctx);
+    if !has_cases
+        && !has_queries
+        && !decls.is_empty()
+        && decls.iter().all(|d| d.starts_with("/* TODO"))
+    {
+        return Ok(render_unrecognized(first, options.fallback));
+    }
+    let mut out: Vec<String> = Vec::new();
+    out.push(proconio_use_line(needs_chars, needs_usize1));
+    for i in alternatives {
+        out.push(format!(
+            "// NOTE: alternative input format: {}",
+            task.input_blocks[i].join(" / ")
+        ));
+    }
+    out.push("fn main() {".to_string());
+    if options.format_comment {
+        out.extend(format_comment(first));
+    }
+
+    if !has_cases && !has_queries {
+        out.push("    input! {".to_string());
+        for d in decls {
+            out.push(format!("        {d}"));
+        }
+        out.push("    }".to_string());
+        for a in after {
+            out.push(format!("    {a}"));
+        }
+        out.push("}".to_string());
+        return Ok(out);
+    }
+
+    // Header
+    out.push("    input! {".to_string());
+    for d in decls {
+        out.push(format!("        {d}"));
+    }
+    out.push("    }".to_string());
+    for a in after {
+        out.push(format!("    {a}"));
+    }
+
+    if has_cases {
+        if task.input_blocks.len() >= 2 {
+            let case = guess_input_from_lines(&task.input_blocks[1], &ctx);
+            push_case_loop(&mut out, case);
+            out.push("}".to_string());
+            import_used_markers(&mut out);
+            return Ok(out);
+        }
+        out.push("    for _ in 0..t {".to_string());
+        out.push("        input! { /* TODO: per-testcase fields */ }".to_string());
+        out.push("        /* TODO: solve testcase */".to_string());
+        out.push("    }".to_string());
+        out.push("}".to_string());
+        return Ok(out);
+    }
+
+    // Queries
+    // Prefer `q`. Otherwise the count is usually the last scalar before the queries.
+    let num_queries = scalars
+        .iter()
+        .find(|s| *s == "q")
+        .or_else(|| scalars.last());
+    match num_queries {
+        Some(q) => out.push(format!("    for _ in 0..{q} {{")),
+        None => out.push("    for _ in 0..0 /* TODO: number of queries */ {".to_string()),
+    }
+    let (leading, qtypes) = parse_query_types(&task.input_blocks[1..]);
+    let mut head = query_decls(&leading, &ctx);
+    head.push(match &qtypes {
+        QueryTypes::Words(_) => "qt: String".to_owned(),
+        _ => "qt: usize".to_owned(),
+    });
+    out.push(format!("        input! {{ {} }}", head.join(", ")));
+    match qtypes {
+        QueryTypes::None => out.push("        /* TODO: per-query fields */".to_string()),
+        QueryTypes::Numbers(types) => {
+            if options.range_query_hint != RangeQueryHint::Off && is_update_and_range_query(&types)
+            {
+                add_range_query_hint(&mut out, options.range_query_hint, scalars.first());
+            }
+            // A match over every type from 0 or 1 is exhaustive if the last one is `_`.
+            let contiguous = types.first().is_some_and(|&(qt, _)| qt <= 1)
+                && types.windows(2).all(|w| w[1].0 == w[0].0 + 1);
+            out.push("        match qt {".to_string());
+            let last = types.len() - 1;
+            for (i, (qt, fields)) in types.into_iter().enumerate() {
+                if contiguous && i == last {
+                    out.push(format!(
+                        "            _ => {}, // {qt}",
+                        query_arm(&fields, &ctx)
+                    ));
+                } else {
+                    out.push(format!("            {qt} => {},", query_arm(&fields, &ctx)));
+                }
+            }
+            if !contiguous {
+                out.push("            _ => unreachable!(),".to_string());
+            }
+            out.push("        }".to_string());
+        }
+        QueryTypes::Words(types) => {
+            out.push("        match &*qt {".to_string());
+            for (qt, fields) in types {
+                out.push(format!(
+                    "            {qt:?} => {},",
+                    query_arm(&fields, &ctx)
+                ));
+            }
+            out.push("            _ => unreachable!(),".to_string());
+            out.push("        }".to_string());
+        }
+    }
+    out.push("        /* TODO: process query */".to_string());
+    out.push("    }".to_string());
+    out.push("}".to_string());
+    import_used_markers(&mut out);
+    Ok(out)
+}
+
+/// Two types of two fields, the second of which are a range like `l r`, as in `1 p x` / `2 l r`.
+fn is_update_and_range_query(types: &[(u32, Vec<String>)]) -> bool {
+    let [(_, update), (_, range)] = types else {
+        return false;
+    };
+    let starts_with = |field: &String, c| snake(field).starts_with(c);
+    update.len() == 2
+        && range.len() == 2
+        && starts_with(&range[0], 'l')
+        && starts_with(&range[1], 'r')
+}
+
+/// Adds the hint before `main`, and with `RangeQueryHint::AcLibrary` a `FenwickTree` of `len`
+/// before the query loop.
+fn add_range_query_hint(out: &mut Vec<String>, hint: RangeQueryHint, len: Option<&String>) {
+    let query_loop = out.iter().rposition(|l| l.starts_with("    for _ in 0.."));
+    if let (RangeQueryHint::AcLibrary, Some(len), Some(query_loop)) = (hint, len, query_loop) {
+        out.insert(
+            query_loop,
+            format!("    let mut bit = FenwickTree::new({len}, 0_u64);"),
+        );
+        out.insert(1, "use ac_library::FenwickTree;".to_owned());
+    }
+    let main = out
+        .iter()
+        .position(|l| l == "fn main() {")
+        .unwrap_or(out.len());
+    out.insert(
+        main,
+        "// NOTE: point updates and range queries. A Fenwick tree or a segment tree fits."
+            .to_owned(),
+    );
+}
+
+/// Rewrites `out[0]`, the `use proconio::..;` line, to import the markers that the per-case or
+/// per-query reads use, besides the ones of the first `input!`.
+fn import_used_markers(out: &mut [String]) {
+    let uses = |marker: &str| {
+        let re = Regex::new(&format!(r"\b{marker}\b")).unwrap();
+        out[1..]
+            .iter()
+            .filter(|l| !l.trim_start().starts_with("//"))
+            .any(|l| re.is_match(l))
+    };
+    let (chars, usize1) = (uses("Chars"), uses("Usize1"));
+    out[0] = proconio_use_line(chars, usize1);
+}
+
+enum QueryTypes {
+    None,
+    /// `(qt, fields)`, sorted by `qt`.
+    Numbers(Vec<(u32, Vec<String>)>),
+    /// `(name, fields)`, in the order of the statement.
+    Words(Vec<(String, Vec<String>)>),
+}
+
+/// Reads the query formats such as `1 x y` or `\mathrm{add} x`. The types are names only if none
+/// of them is a number. Also returns the fields before the type when it is not the first token, as
+/// in `k 1 x` / `k 2 y`.
+fn parse_query_types(blocks: &[Vec<String>]) -> (Vec<String>, QueryTypes) {
+    let word_re =
+        Regex::new(r"^(?:\\(?:mathrm|texttt|text)\{([A-Za-z]+)\}|([a-z][A-Za-z]+))$").unwrap();
+    let normalize = |toks: &[&str]| -> Vec<String> {
+        normalize_line(&toks.join(" "))
+            .split_whitespace()
+            .map(ToOwned::to_owned)
+            .collect()
+    };
+
+    let lines = blocks
+        .iter()
+        .filter_map(|b| match &b[..] {
+            [line] if !line.trim().is_empty() => Some(line.split_whitespace().collect::<Vec<_>>()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let heads_are_types = lines
+        .iter()
+        .all(|toks| toks[0].parse::<u32>().is_ok() || word_re.is_match(toks[0]));
+    let column = if heads_are_types {
+        0
+    } else {
+        type_column(&lines)
+    };
+    let leading = lines
+        .first()
+        .map_or(vec![], |toks| normalize(&toks[..column]));
+
+    let mut numbers = vec![];
+    let mut words = vec![];
+    for toks in &lines {
+        let head = toks[column];
+        let rest = normalize(&toks[column + 1..]);
+        if let Ok(qt) = head.parse::<u32>() {
+            numbers.push((qt, rest));
+        } else if let Some(caps) = word_re.captures(head) {
+            let word = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str();
+            words.push((word.to_owned(), rest));
+        }
+    }
+    let types = if !numbers.is_empty() {
+        numbers.sort_by_key(|&(qt, _)| qt);
+        QueryTypes::Numbers(numbers)
+    } else if !words.is_empty() {
+        QueryTypes::Words(words)
+    } else {
+        QueryTypes::None
+    };
+    (leading, types)
+}
+
+/// The column of the query type when it is not the first: the one that is a distinct small number
+/// in every query, after the same fields. `0` if there is no such column.
+fn type_column(lines: &[Vec<&str>]) -> usize {
+    const MAX_TYPE: u32 = 99;
+
+    // One query tells nothing.
+    if lines.len() < 2 {
+        return 0;
+    }
+    let is_type = |c: usize| {
+        let mut seen = HashSet::new();
+        lines.iter().all(|toks| {
+            toks[..c] == lines[0][..c]
+                && toks[c]
+                    .parse::<u32>()
+                    .is_ok_and(|qt| qt <= MAX_TYPE && seen.insert(qt))
+        })
+    };
+    let width = lines.iter().map(Vec::len).min().unwrap_or(0);
+    (1..width).find(|&c| is_type(c)).unwrap_or(0)
+}
+
+/// Reads the fields of a query, each a scalar or a `a_1 a_2 \ldots a_k` array.
+fn query_arm(fields: &[String], ctx: &GuessContext<'_>) -> String {
+    if fields.is_empty() {
+        return "{}".to_owned();
+    }
+    format!("{{ input! {{ {} }} }}", query_decls(fields, ctx).join(", "))
+}
+
+/// The declarations of the fields of a query, as in `x: usize` or `a: [usize; k]`.
+fn query_decls(fields: &[String], ctx: &GuessContext<'_>) -> Vec<String> {
+    let mut decls = vec![];
+    let mut i = 0;
+    while i < fields.len() {
+        let array = fields
+            .get(i..i + 4)
+            .and_then(|toks| parse_1d_array_line(&toks.join(" "), ctx));
+        if let Some((name, ty)) = array {
+            decls.push(format!("{name}: {ty}"));
+            i += 4;
+        } else {
+            let ty = match statement_says_string(&fields[i], ctx) {
+                Some(true) => "Chars",
+                _ => "usize",
+            };
+            decls.push(format!("{}: {ty}", snake(&fields[i])));
+            i += 1;
+        }
+    }
+    decls
+}
+
+fn format_comment(lines: &[String]) -> Vec<String> {
+    let mut out = vec!["    // format:".to_string()];
+    out.extend(lines.iter().map(|l| format!("    //   {l}")));
+    out
+}
+
+/// Whether the format is `T` alone followed by the `<pre>` of a case, which the statement says in
+/// prose like "最初にテストケース数 T が与えられる" instead of a `\mathrm{case}_i` line.
+fn has_cases_in_prose(task: &TaskSection) -> bool {
+    match &task.input_blocks[..] {
+        [first, _, ..] => {
+            matches!(&first[..], [t] if t.trim() == "T") && says_t_testcases(&task.statement)
+        }
+        _ => false,
+    }
+}
+
+/// Whether `statement` mentions test cases next to `T`, as in "テストケース数 T" or "T test cases".
+fn says_t_testcases(statement: &str) -> bool {
+    let re = Regex::new(
+        r"(?i)(?:テストケース|test\s*cases?)[^。.\n]{0,16}(?:^|[^A-Za-z0-9_])T(?:[^A-Za-z0-9_]|$)|(?:^|[^A-Za-z0-9_])T(?:[^A-Za-z0-9_]|$)[^。.\n]{0,16}(?:テストケース|test\s*cases?)",
+    )
+    .unwrap();
+    re.is_match(statement)
+}
+
+/// The lines after `T` when every case is written inline in the same `<pre>`, like
+/// `T` / `N` / `A_1 A_2 \ldots A_N`.
+fn inline_case_lines(task: &TaskSection) -> Option<&[String]> {
+    let (first, rest) = task.input_blocks.first()?.split_first()?;
+    if first.trim() != "T" || rest.is_empty() {
+        return None;
+    }
+    // `T` may as well be a string. The samples tell.
+    let t_is_number = task.samples.iter().all(|sample| {
+        sample
+            .lines()
+            .next()
+            .is_some_and(|l| l.trim().parse::<usize>().is_ok())
+    });
+    t_is_number.then_some(rest)
+}
+
+fn push_case_loop(out: &mut Vec<String>, case: GuessedInput) {
+    out.push("    for _ in 0..t {".to_string());
+    out.push("        input! {".to_string());
+    for d in case.decls {
+        out.push(format!("            {d}"));
+    }
+    out.push("        }".to_string());
+    for a in case.after {
+        out.push(format!("        {a}"));
+    }
+    out.push("        /* TODO: solve testcase */".to_string());
+    out.push("    }".to_string());
+}
+
+/// `index` is the position of `task` in `task.html`.
+fn src_file_name(
+    index: usize,
+    task: &TaskSection,
+    options: &TemplateOptions,
+) -> anyhow::Result<Utf8PathBuf> {
+    if let Some(name) = options.file_name_overrides.get(&task.letter) {
+        let path = Utf8Path::new(name);
+        if path.file_name().is_none() || !is_plain_relative(path) {
+            bail!("`{name}` is not a relative path without `.` or `..`");
+        }
+        return Ok(path.to_owned());
+    }
+    let title = options.name_with_title.then_some(&*task.title);
+    Ok(task_filename(index, &task.letter, title))
+}
+
+/// Has no root, `.` or `..`, so that it stays inside the directory it is joined to.
+fn is_plain_relative(path: &Utf8Path) -> bool {
+    path.components()
+        .all(|c| matches!(c, Utf8Component::Normal(_)))
+}
+
+/// `<letter>.rs`, or `<letter>-<kebab-title>.rs` with the title cut at a word boundary so that it
+/// stays short. The letter is kept to `[a-z0-9_-]`, and is `task-<index + 1>` if nothing is left.
+fn task_filename(index: usize, letter: &str, title: Option<&str>) -> Utf8PathBuf {
+    const MAX_TITLE_LEN: usize = 32;
+
+    let letter = letter
+        .to_kebab_case()
+        .chars()
+        .filter(|&c| matches!(c, 'a'..='z' | '0'..='9' | '_' | '-'))
+        .collect::<String>();
+    let letter = match letter.trim_matches('-') {
+        "" => format!("task-{}", index + 1),
+        letter => letter.to_owned(),
+    };
+    let words = title.unwrap_or_default().to_kebab_case();
+    let mut title = String::new();
+    for word in words.split('-') {
+        let word = word
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect::<String>();
+        if word.is_empty() {
+            continue;
+        }
+        if !title.is_empty() && title.len() + 1 + word.len() > MAX_TITLE_LEN {
+            break;
+        }
+        if !title.is_empty() {
+            title.push('-');
+        }
+        title += &word[..word.len().min(MAX_TITLE_LEN)];
+    }
+    if title.is_empty() {
+        Utf8PathBuf::from(letter).with_extension("rs")
+    } else {
+        Utf8PathBuf::from(format!("{letter}-{title}")).with_extension("rs")
+    }
+}
+
+/// Counts the lines with a `TODO` placeholder, including the ones for cases and queries.
+fn count_todos(content: &str) -> usize {
+    content.lines().filter(|l| l.contains("TODO")).count()
+}
+
+/// Finds the `TODO`s and `NOTE`s in `content`. They are read from the file as it is written, so
+/// that the lines are right after wrapping or `--update-input-only`.
+fn diagnose(content: &str) -> Vec<Diagnostic> {
+    let re = Regex::new(r"\b(?:TODO|NOTE)\b.*").unwrap();
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let message = re
+                .find(line)?
+                .as_str()
+                .split("*/")
+                .next()
+                .unwrap()
+                .trim_end();
+            Some(Diagnostic {
+                line: i + 1,
+                message: message.to_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Tells which options changed `content` compared to `TemplateOptions::default()`.
+fn applied_options(
+    task: &TaskSection,
+    options: &TemplateOptions,
+    content: &str,
+) -> Vec<&'static str> {
+    let has_override = options.file_name_overrides.contains_key(&task.letter);
+    let has_url = options.contest.is_some() && options.task_slugs.contains_key(&task.letter);
+    [
+        ("wrap_in_module", options.wrap_in_module),
+        (
+            "bool_grid",
+            options.bool_grid
+                && content
+                    .lines()
+                    .any(|l| l.trim_start().starts_with("let ") && l.contains("Vec<Vec<bool>>")),
+        ),
+        (
+            "digit_grid",
+            options.digit_grid
+                && content
+                    .lines()
+                    .any(|l| l.trim_start().starts_with("let ") && l.contains("Vec<Vec<usize>>")),
+        ),
+        (
+            "input_source",
+            options.input_source != InputSource::Stdin && options.reader == Reader::Proconio,
+        ),
+        ("reader", options.reader != Reader::Proconio),
+        ("file_name_overrides", has_override),
+        (
+            "compact_ints",
+            options.compact_ints && content.contains("u32"),
+        ),
+        ("allow_clippy", options.allow_clippy),
+        ("contest", has_url),
+        ("task_slugs", has_url),
+        ("name_with_title", options.name_with_title && !has_override),
+        (
+            "one_indexed",
+            match options.one_indexed {
+                OneIndexed::Keep => false,
+                OneIndexed::Annotate => content.contains("are 1-indexed"),
+                OneIndexed::Usize1 => content.contains("Usize1"),
+                OneIndexed::Convert => content.contains("|&x| x - 1"),
+            },
+        ),
+        ("proconio_version", options.proconio_version.is_some()),
+        ("format_comment", options.format_comment),
+        (
+            "permutation_usize1",
+            options.permutation_usize1 && content.contains("// permutation"),
+        ),
+        (
+            "suppress_unused",
+            options.suppress_unused && content.contains("let _ = "),
+        ),
+        ("yes_no", options.yes_no && content.contains("fn yn(")),
+        (
+            "print_array",
+            options.print_array && content.contains("fn print_array<"),
+        ),
+        ("validate_syntax", options.validate_syntax),
+        ("fastout", options.fastout && content.contains("#[fastout]")),
+        (
+            "batch_output",
+            options.batch_output && content.contains("BufWriter::new"),
+        ),
+        (
+            "range_query_hint",
+            content.contains("// NOTE: point updates and range queries."),
+        ),
+    ]
+    .iter()
+    .filter(|&&(_, applied)| applied)
+    .map(|&(name, _)| name)
+    .collect()
+}
+
+/// Replaces the first `input! { .. }` in `existing` with the first one in `generated`, keeping
+/// everything else. `existing` is returned as it is if either of them has none.
+pub(crate) fn splice_input_block(existing: &str, generated: &str) -> String {
+    match (find_input_block(existing), find_input_block(generated)) {
+        (Some(old), Some(new)) => [
+            &existing[..old.start],
+            &generated[new],
+            &existing[old.end..],
+        ]
+        .concat(),
+        _ => existing.to_owned(),
+    }
+}
+
+/// The byte range from `input!` to its closing brace. Braces in string literals are not counted.
+fn find_input_block(code: &str) -> Option<Range<usize>> {
+    let mut from = 0;
+    while let Some(pos) = code[from..].find("input!") {
+        let start = from + pos;
+        from = start + "input!".len();
+        let preceded_by_ident = code[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let rest = &code[from..];
+        let open = from + (rest.len() - rest.trim_start().len());
+        if preceded_by_ident || !code[open..].starts_with('{') {
+            continue;
+        }
+
+        let mut depth = 0;
+        let mut chars = code[open..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(start..open + i + 1);
+                    }
+                }
+                '"' => {
+                    while let Some((_, c)) = chars.next() {
+                        match c {
+                            '\\' => {
+                                chars.next();
+                            }
+                            '"' => break,
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        return None;
+    }
+    None
+}
+
+/// Generates a template from an input format alone, such as one copied from a statement.
+///
+/// Blank lines separate blocks, like the `<pre>`s of a statement. Plain-text formats (`A_1 ...
+/// A_N` and `:`) are accepted as well as LaTeX ones.
+pub fn generate_from_format(format: &str) -> anyhow::Result<String> {
+    let mut input_blocks = vec![vec![]];
+    for line in format.lines() {
+        let line = latexify(line);
+        if !line.is_empty() {
+            input_blocks.last_mut().unwrap().push(line);
+        } else if !input_blocks.last().unwrap().is_empty() {
+            input_blocks.push(vec![]);
+        }
+    }
+    input_blocks.retain(|b| !b.is_empty());
+    if input_blocks.is_empty() {
+        bail!("the input format is empty");
+    }
+    let task = TaskSection {
+        letter: "A".to_owned(),
+        title: String::new(),
+        input_blocks,
+        samples: vec![],
+        constraints: vec![],
+        input_is_image: false,
+        modulus: None,
+        points: None,
+        statement: String::new(),
+        output: String::new(),
+    };
+    render_section(&task, &TemplateOptions::default())
+}
+
+/// Gives the path of a task from its letter and title. See `generate_templates`.
+pub type NameFn<'a> = dyn Fn(&str, &str) -> Utf8PathBuf + 'a;
+
+/// Generates the sources for `task.html` (`tasks_print` of AtCoder) in `dest_dir`, with `options`
+/// written as the `input-template` table of `cargo-compete.toml`, and returns them as
+/// `(path, content)` in the order of the tasks. Empty if there is no `task.html`.
+///
+/// `name_fn`, if any, gives the path of each task from its letter and title, relative to
+/// `dest_dir` instead of `src/bin/<letter>.rs`. It is an error if a path is absolute, has `.` or
+/// `..`, or is given to two tasks.
+///
+/// The sources are not written, but `src/bin` and the parse cache of `task.html` are. The
+/// warnings and the progress go to `reporter`.
+pub fn generate_templates(
+    dest_dir: &Utf8Path,
+    options: &str,
+    name_fn: Option<&NameFn<'_>>,
+    reporter: &mut dyn Reporter,
+) -> anyhow::Result<Vec<(Utf8PathBuf, String)>> {
+    let options =
+        toml::from_str::<TemplateOptions>(options).with_context(|| "invalid `input-template`")?;
+    let Some(mut generated) = generate_template(dest_dir, &AtCoder, &options, name_fn, reporter)?
+    else {
+        return Ok(vec![]);
+    };
+    let sources = generated
+        .order
+        .into_iter()
+        .map(|path| {
+            let content = generated.sources.remove(&path).unwrap().content;
+            (path, content)
+        })
+        .collect();
+    Ok(sources)
+}
+
+/// Renders each task of `html` (`tasks_print` of AtCoder) by its letter, with `options` written as
+/// the `input-template` table of `cargo-compete.toml`. Nothing is read or written, so that saved
+/// pages can be compared with the sources expected from them. Two tasks with the same letter are
+/// an error.
+pub fn generate_template_from_html(
+    html: &str,
+    options: &str,
+) -> anyhow::Result<BTreeMap<String, String>> {
+    let options =
+        toml::from_str::<TemplateOptions>(options).with_context(|| "invalid `input-template`")?;
+    let mut out = BTreeMap::new();
+    for task in parse_task_sections(html) {
+        if out.contains_key(&task.letter) {
+            bail!("more than one task is lettered {}", task.letter);
+        }
+        let content = render_section(&task, &options)
+            .with_context(|| format!("could not render {}", task.letter))?;
+        out.insert(task.letter, content);
+    }
+    Ok(out)
+}
+
+/// Rewrites a plain-text format line in the LaTeX-ish notation of statements.
+fn latexify(line: &str) -> String {
+    let line = line.trim().trim_matches('$').trim();
+    if [":", "⋮"].contains(&line) {
+        return "\\vdots".to_owned();
+    }
+    line.replace('…', "\\ldots")
+        .replace('⋯', "\\cdots")
+        .split_whitespace()
+        .map(|tok| if tok == "..." { "\\ldots" } else { tok })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `task.parsed.json`, the sections parsed from `task.html`.
+#[derive(Serialize, Deserialize)]
+struct ParsedTasks {
+    /// The version of cargo-compete that parsed them.
+    version: String,
+    html_hash: String,
+    sections: Vec<TaskSection>,
+}
+
+/// Reads the sections from `task.parsed.json` if it was made from the same `task.html`, and
+/// otherwise parses `html` and rewrites the file. Failing to write it is only warned.
+fn load_sections(
+    dest_dir: &Utf8Path,
+    judge: &dyn Judge,
+    html: &str,
+    shell: &mut dyn Reporter,
+) -> anyhow::Result<Vec<TaskSection>> {
+    let cache_path = dest_dir.join("task.parsed.json");
+    let version = env!("CARGO_PKG_VERSION");
+    let html_hash = format!("{:016x}", fnv1a(html.as_bytes()));
+
+    // A broken cache is only a reason to parse again.
+    if let Ok(cache) = crate::fs::read_json::<ParsedTasks, _>(&cache_path) {
+        if cache.version == version && cache.html_hash == html_hash {
+            return Ok(cache.sections);
+        }
+    }
+
+    let sections = judge.parse_sections(html);
+    let cache = serde_json::to_string(&ParsedTasks {
+        version: version.to_owned(),
+        html_hash,
+        sections: sections.clone(),
+    })?;
+    if let Err(err) = crate::fs::write_atomic(&cache_path, cache) {
+        shell.warn(format!("could not cache the parsed tasks ({err:#})"))?;
+    }
+    Ok(sections)
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is the same across builds.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Whether `letter` is `pat` ignoring case, or is in the range `pat` like `D-F` by its first
+/// character (so `E-F` has `Ex` and `F2`).
+fn letter_matches(pat: &str, letter: &str) -> bool {
+    let first = |s: &str| {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c.to_ascii_uppercase()),
+            _ => None,
+        }
+    };
+    if let Some((from, to)) = pat.split_once('-') {
+        if let (Some(from), Some(to)) = (first(from), first(to)) {
+            return letter
+                .chars()
+                .next()
+                .is_some_and(|c| (from..=to).contains(&c.to_ascii_uppercase()));
+        }
+    }
+    pat.eq_ignore_ascii_case(letter)
+}
+
+/// Whether `rendered` is worth reading the task page for.
+fn is_unrecognized(rendered: &anyhow::Result<String>) -> bool {
+    rendered
+        .as_ref()
+        .map_or(true, |content| content.contains(UNRECOGNIZED_NOTE))
+}
+
+/// Runs `render`, turning a panic into an error so that one odd task does not stop the others.
+fn catch_render(render: impl FnOnce() -> anyhow::Result<String>) -> anyhow::Result<String> {
+    panic::catch_unwind(AssertUnwindSafe(render)).unwrap_or_else(|payload| {
+        let msg = payload
+            .downcast_ref::<&str>()
+            .map(|s| (*s).to_owned())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown cause".to_owned());
+        bail!("panicked ({msg})")
+    })
+}
+
+/// Parses the page of `task` alone, with the letter it has in `task.html`.
+fn refetch_section(
+    judge: &dyn Judge,
+    task: &TaskSection,
+    options: &TemplateOptions,
+    shell: &mut dyn Reporter,
+) -> anyhow::Result<Option<TaskSection>> {
+    let (Some(contest), Some(slug)) = (&options.contest, options.task_slugs.get(&task.letter))
+    else {
+        return Ok(None);
+    };
+    let Some(html) = judge.fetch_task(contest, slug, &options.cookies_path, shell)? else {
+        return Ok(None);
+    };
+    let section = judge
+        .parse_sections(&String::from_utf8_lossy(&html))
+        .into_iter()
+        .next()
+        .map(|section| TaskSection {
+            letter: task.letter.clone(),
+            ..section
+        });
+    Ok(section)
+}
+
+/// `package.name` of `dest_dir/Cargo.toml`.
+fn package_name(dest_dir: &Utf8Path) -> Option<String> {
+    let manifest = fs::read_to_string(dest_dir.join("Cargo.toml")).ok()?;
+    let manifest = manifest.parse::<toml::Value>().ok()?;
+    Some(manifest.get("package")?.get("name")?.as_str()?.to_owned())
+}
+
+/// `name_fn`, if any, gives the path of each task from its letter and title, relative to
+/// `dest_dir` instead of `src/bin/<letter>.rs`.
+pub(crate) fn generate_template(
+    dest_dir: &Utf8Path,
+    judge: &dyn Judge,
+    options: &TemplateOptions,
+    name_fn: Option<&NameFn<'_>>,
+    shell: &mut dyn Reporter,
+) -> anyhow::Result<Option<GeneratedTemplates>> {
+    let task_path = dest_dir.join("task.html");
+    if !task_path.exists() {
+        return Ok(None);
+    }
+    let html =
+        fs::read_to_string(&task_path).with_context(|| format!("failed to read {task_path}"))?;
+    // What an interrupted download leaves.
+    if html.trim().len() < MIN_TASK_HTML_LEN && !judge.recognizes(&html) {
+        shell.warn(format!(
+            "`{task_path}` is almost empty. Remove it and run again to re-download it.",
+        ))?;
+        return Ok(None);
+    }
+    if !judge.recognizes(&html) {
+        shell.warn(format!(
+            "`{task_path}` does not look like a page we know. The page format may have changed, \
+             so the templates are best-effort.",
+        ))?;
+    }
+    let sections = load_sections(dest_dir, judge, &html, shell)?;
+    let src_dir = match &options.bin_dir {
+        Some(dir) if !dir.as_str().is_empty() && is_plain_relative(dir) => dest_dir.join(dir),
+        Some(dir) => {
+            shell.warn(format!(
+                "ignoring `bin-dir`: `{dir}` is not a relative path without `.` or `..`",
+            ))?;
+            dest_dir.join("src").join("bin")
+        }
+        None => dest_dir.join("src").join("bin"),
+    };
+    let package_name = options
+        .bin_targets
+        .then(|| package_name(dest_dir))
+        .flatten();
+    let mut out = GeneratedTemplates::default();
+    // The letters the paths of `name_fn` were given to.
+    let mut named = HashMap::new();
+    for (index, task) in sections.iter().enumerate() {
+        if let Some(letters) = &options.letters {
+            if !letters.iter().any(|pat| letter_matches(pat, &task.letter)) {
+                continue;
+            }
+        }
+        let src_path = match name_fn.map(|f| f(&task.letter, &task.title)) {
+            Some(path) => {
+                if path.as_str().is_empty() || !is_plain_relative(&path) {
+                    bail!(
+                        "{}: `{path}` is not a relative path without `.` or `..`",
+                        task.letter,
+                    );
+                }
+                if let Some(other) = named.insert(path.clone(), &task.letter) {
+                    bail!("both {other} and {} are named `{path}`", task.letter);
+                }
+                dest_dir.join(path)
+            }
+            None => match src_file_name(index, task, options) {
+                Ok(file_name) => src_dir.join(file_name),
+                Err(err) => {
+                    shell.warn(format!("ignoring the file name for {}: {err}", task.letter))?;
+                    src_dir.join(task_filename(index, &task.letter, None))
+                }
+            },
+        };
+        let src_path = if out.sources.contains_key(&src_path) {
+            let titled = src_dir.join(task_filename(index, &task.letter, Some(&task.title)));
+            // `task-<index + 1>.rs` is only taken by a letter that spells it.
+            let alt = if out.sources.contains_key(&titled) {
+                src_dir.join(task_filename(index, "", None))
+            } else {
+                titled
+            };
+            shell.warn(format!(
+                "`{src_path}` is already used by another {}. Using `{alt}` instead.",
+                task.letter,
+            ))?;
+            alt
+        } else {
+            src_path
+        };
+        let mut task = task;
+        let mut rendered = catch_render(|| render_section(task, options));
+        let refetched;
+        if options.refetch_task_pages && is_unrecognized(&rendered) && offline() {
+            shell.warn(format!(
+                "{}: not reading the task page since `ACCC_OFFLINE` is set",
+                task.letter,
+            ))?;
+        } else if options.refetch_task_pages && is_unrecognized(&rendered) {
+            match refetch_section(judge, task, options, shell) {
+                Ok(Some(section)) => {
+                    refetched = section;
+                    let again = catch_render(|| render_section(&refetched, options));
+                    if is_unrecognized(&again) {
+                        shell.warn(format!(
+                            "{}: the task page does not tell the input format either",
+                            task.letter,
+                        ))?;
+                    } else {
+                        task = &refetched;
+                        rendered = again;
+                    }
+                }
+                Ok(None) => shell.warn(format!("{}: no task page to read", task.letter))?,
+                Err(err) => shell.warn(format!(
+                    "{}: could not read the task page ({err:#})",
+                    task.letter,
+                ))?,
+            }
+        }
+        match rendered {
+            Ok(content) => {
+                let content = if options.update_input_only && src_path.exists() {
+                    let existing = fs::read_to_string(&src_path)
+                        .with_context(|| format!("failed to read {src_path}"))?;
+                    if find_input_block(&existing).is_none() {
+                        shell.warn(format!("`{src_path}` has no `input!` to update"))?;
+                    }
+                    splice_input_block(&existing, &content)
+                } else {
+                    content
+                };
+                for &(prefix, name, version) in TEMPLATE_DEPENDENCIES {
+                    if content.contains(prefix) {
+                        let version = match options.proconio_version {
+                            Some(v) if name == "proconio" => v.requirement(),
+                            _ => version,
+                        };
+                        out.dependencies.insert(name, version);
+                    }
+                }
+                let todos = count_todos(&content);
+                if todos > 0 {
+                    shell.warn(format!(
+                        "{}: `{src_path}` has {todos} TODO(s) to fill in by hand",
+                        task.letter,
+                    ))?;
+                }
+                let applied_options = applied_options(task, options, &content);
+                let diagnostics = diagnose(&content);
+                if options.bin_targets {
+                    let letter = task.letter.to_kebab_case();
+                    out.bin_targets.push(BinTarget {
+                        name: match &package_name {
+                            Some(package_name) => format!("{package_name}-{letter}"),
+                            None => letter,
+                        },
+                        path: src_path
+                            .strip_prefix(dest_dir)
+                            .unwrap_or(&src_path)
+                            .components()
+                            .map(|c| c.as_str())
+                            .collect::<Vec<_>>()
+                            .join("/"),
+                    });
+                }
+                // So that the sources can be written without creating any directory.
+                if let Some(dir) = src_path.parent() {
+                    crate::fs::create_dir_all(dir)?;
+                }
+                out.order.push(src_path.clone());
+                out.sources.insert(
+                    src_path,
+                    GeneratedSource {
+                        letter: task.letter.clone(),
+                        content,
+                        applied_options,
+                        diagnostics,
+                        points: task.points,
+                    },
+                );
+            }
+            Err(err) => {
+                shell.warn(format!("render_section failed at {}: {err}", task.letter))?;
+            }
+        }
+    }
+    Ok(Some(out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        catch_render, count_todos, detect_schema, diagnose, generate_from_format,
+        generate_template, letter_matches, parse_constraints, parse_task_sections,
+        parse_yukicoder_sections, render_section, splice_input_block, src_file_name, task_filename,
+        Bounds, Diagnostic, HtmlSchema, Modulus, NameFn, RangeQueryHint, Reader, TaskSection,
+        TemplateOptions,
+    };
+    use crate::{
+        shell::{Reporter, Shell},
+        web::{
+            cookie_atcoder_py::CookieTarget,
+            judge::{AtCoder, Judge},
+        },
+    };
+    use camino::{Utf8Path, Utf8PathBuf};
+    use maplit::hashmap;
+    use pretty_assertions::assert_eq;
+    use std::{collections::BTreeMap, fmt, io, path::Path};
+    use url::Url;
+
+    /// Keeps the warnings to look at.
+    #[derive(Default)]
+    struct Warnings(Vec<String>);
+
+    impl Reporter for Warnings {
+        fn report_status(&mut self, _: &dyn fmt::Display, _: &dyn fmt::Display) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn report_warning(&mut self, message: &dyn fmt::Display) -> io::Result<()> {
+            self.0.push(message.to_string());
+            Ok(())
+        }
+    }
+
+    /// The defaults, but with every generated code parsed.
+    fn validated() -> TemplateOptions {
+        TemplateOptions {
+            validate_syntax: true,
+            ..TemplateOptions::default()
+        }
+    }
+
+    fn section(letter: &str, blocks: &[&[&str]]) -> TaskSection {
+        TaskSection {
+            letter: letter.to_string(),
+            title: String::new(),
+            input_blocks: blocks
+                .iter()
+                .map(|b| b.iter().map(|l| l.to_string()).collect())
+                .collect(),
+            samples: vec![],
+            constraints: vec![],
+            input_is_image: false,
+            modulus: None,
+            points: None,
+            statement: String::new(),
+            output: String::new(),
+        }
+    }
+
+    #[test]
+    fn grid_size_on_separate_lines() -> anyhow::Result<()> {
+        let options = TemplateOptions {
+            bool_grid: true,
+            ..validated()
+        };
+
+        // `N` is not taken for the height.
+        let mut task = section("A", &[&["N", "S_1", r"\vdots", "S_N"]]);
+        task.samples = vec!["2\n.#.\n##.\n".to_string()];
+        assert!(render_section(&task, &options)?.contains("s: [Chars; n],"));
+        Ok(())
+    }
+
+    #[test]
+    fn maze_grid() -> anyhow::Result<()> {
+        let mut task = section("A", &[&["H W", "S_1", r"\vdots", "S_H"]]);
+        task.samples = vec!["2 3\nabc\nde.\n".to_string()];
+        let options = TemplateOptions {
+            bool_grid: true,
+            ..validated()
+        };
+        let rendered = render_section(&task, &options)?;
+        assert!(!rendered.contains("bool"));
+        Ok(())
+    }
+
+    #[test]
+    fn todos() -> anyhow::Result<()> {
+        let rendered = render_section(&section("A", &[&["N", r"L \ldots R"]]), &validated())?;
+        assert_eq!(1, count_todos(&rendered));
+
+        let rendered = render_section(
+            &section("A", &[&[r"\mathrm{query}_1"], &["1 x"]]),
+            &validated(),
+        )?;
+        // The number of queries and the processing of each query.
+        assert_eq!(2, count_todos(&rendered));
+        Ok(())
+    }
+
+    #[test]
+    fn diagnostics() -> anyhow::Result<()> {
+        let rendered = render_section(
+            &section("A", &[&[r"\mathrm{query}_1"], &["1 x"]]),
+            &TemplateOptions {
+                wrap_in_module: true,
+                ..validated()
+            },
+        )?;
+        assert_eq!(
+            vec![
+                Diagnostic {
+                    line: 6,
+                    message: "TODO: number of queries".to_owned(),
+                },
+                Diagnostic {
+                    line: 11,
+                    message: "TODO: process query".to_owned(),
+                },
+            ],
+            diagnose(&rendered),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn file_name_overrides() -> anyhow::Result<()> {
+        let options = TemplateOptions {
+            file_name_overrides: hashmap!(
+                "A".to_owned() => "solve_a.rs".to_owned(),
+                "B".to_owned() => "../b.rs".to_owned(),
+            ),
+            ..validated()
+        };
+        assert_eq!(
+            Utf8Path::new("solve_a.rs"),
+            src_file_name(0, &section("A", &[]), &options)?,
+        );
+        assert!(src_file_name(0, &section("B", &[]), &options).is_err());
+        assert_eq!(
+            Utf8Path::new("c.rs"),
+            src_file_name(0, &section("C", &[]), &options)?,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn odd_letters() -> anyhow::Result<()> {
+        assert_eq!(Utf8Path::new("a.rs"), task_filename(0, "A", None));
+        assert_eq!(Utf8Path::new("ex.rs"), task_filename(7, "Ex", None));
+        assert_eq!(Utf8Path::new("f2.rs"), task_filename(5, "F2", None));
+        assert_eq!(Utf8Path::new("a-b.rs"), task_filename(0, "A/B", None));
+        assert_eq!(Utf8Path::new("x.rs"), task_filename(0, "../x", None));
+        assert_eq!(Utf8Path::new("task-3.rs"), task_filename(2, "α", None));
+        assert_eq!(Utf8Path::new("task-4.rs"), task_filename(3, "?!", None));
+        assert_eq!(
+            Utf8Path::new("task-1-two-sides.rs"),
+            task_filename(0, "", Some("Two Sides")),
+        );
+
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-input-template-")
+            .tempdir()?;
+        let dest_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+        std::fs::write(
+            dest_dir.join("task.html"),
+            r#"<span class="h2">A - Same</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>
+<span class="h2">A - Same</span>
+<h3>入力</h3><pre>N M</pre><h3>出力</h3>
+<span class="h2">A - Same</span>
+<h3>入力</h3><pre>N K</pre><h3>出力</h3>"#,
+        )?;
+        let generated = generate_template(
+            dest_dir,
+            &AtCoder,
+            &validated(),
+            None,
+            &mut Shell::from_read_write(Box::new(&b""[..]), Box::new(vec![])),
+        )?
+        .unwrap();
+        let mut paths = generated.sources.into_keys().collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            vec![
+                dest_dir.join("src").join("bin").join("a-same.rs"),
+                dest_dir.join("src").join("bin").join("a.rs"),
+                dest_dir.join("src").join("bin").join("task-3.rs"),
+            ],
+            paths,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn name_with_title() -> anyhow::Result<()> {
+        let options = TemplateOptions {
+            name_with_title: true,
+            ..validated()
+        };
+        let mut task = section("A", &[]);
+        task.title = "Two Sides".to_owned();
+        assert_eq!(
+            Utf8Path::new("a-two-sides.rs"),
+            src_file_name(0, &task, &options)?,
+        );
+        task.title = "The Longest Title That Anyone Has Ever Written".to_owned();
+        assert_eq!(
+            Utf8Path::new("a-the-longest-title-that-anyone.rs"),
+            src_file_name(0, &task, &options)?,
+        );
+        task.title = "高橋くん".to_owned();
+        assert_eq!(Utf8Path::new("a.rs"), src_file_name(0, &task, &options)?);
+        Ok(())
+    }
+
+    #[test]
+    fn constraints() {
+        let bounds = parse_constraints(&[
+            r"1 \leq N \leq 2 \times 10^5".to_owned(),
+            r"-10^9 \le X_i, Y_i \le 10^{9}".to_owned(),
+            r"1 \le A_i \le N".to_owned(),
+            r"K \lt 998244353".to_owned(),
+            "入力は全て整数である。".to_owned(),
+        ]);
+        let bounded = |lower, upper| Bounds {
+            lower: Some(lower),
+            upper: Some(upper),
+            upper_symbol: None,
+        };
+        assert_eq!(Some(&bounded(1, 200_000)), bounds.get("n"));
+        assert_eq!(
+            Some(&bounded(-1_000_000_000, 1_000_000_000)),
+            bounds.get("x")
+        );
+        assert_eq!(
+            Some(&bounded(-1_000_000_000, 1_000_000_000)),
+            bounds.get("y")
+        );
+        assert_eq!(
+            Some(&Bounds {
+                upper_symbol: Some("n".to_owned()),
+                ..bounded(1, 200_000)
+            }),
+            bounds.get("a"),
+        );
+        assert_eq!(None, bounds["k"].lower);
+        assert_eq!(Some(998_244_353), bounds["k"].upper);
+        assert_eq!(5, bounds.len());
+    }
+
+    #[test]
+    fn image_only_input() -> anyhow::Result<()> {
+        let sections = parse_task_sections(
+            r#"<span class="h2">A - Picture</span>
+<div class="part"><section><h3>入力</h3><p>入力は以下の形式で与えられる。</p>
+<p><img src="https://img.atcoder.jp/old/a_input.png"></p></section></div>
+<div class="part"><section><h3>出力</h3></section></div>"#,
+        );
+        assert_eq!(1, sections.len());
+        assert!(sections[0].input_is_image);
+        Ok(())
+    }
+
+    #[test]
+    fn query_count() -> anyhow::Result<()> {
+        let queries = |first: &'static [&'static str]| {
+            section(
+                "A",
+                &[
+                    first,
+                    &[r"\mathrm{query}_1", r"\vdots", r"\mathrm{query}_M"],
+                    &["1 x"],
+                    &["2"],
+                ],
+            )
+        };
+        let options = validated();
+
+        let rendered = render_section(&queries(&["Q N"]), &options)?;
+        assert!(rendered.contains("    for _ in 0..q {"));
+        let rendered = render_section(&queries(&["N M"]), &options)?;
+        assert!(rendered.contains("    for _ in 0..m {"));
+        let rendered = render_section(&queries(&[r"A_1 A_2 \ldots A_5"]), &options)?;
+        assert!(rendered.contains("    for _ in 0..0 /* TODO: number of queries */ {"));
+        Ok(())
+    }
+
+    #[test]
+    fn disambiguate_duplicate_letters() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-input-template-")
+            .tempdir()?;
+        let dest_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+        std::fs::write(
+            dest_dir.join("task.html"),
+            r#"<span class="h2">A - Two Sides</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>
+<span class="h2">A - Lucky Seven</span>
+<h3>入力</h3><pre>N M</pre><h3>出力</h3>"#,
+        )?;
+
+        let generated = generate_template(
+            dest_dir,
+            &AtCoder,
+            &validated(),
+            None,
+            &mut Shell::from_read_write(Box::new(&b""[..]), Box::new(vec![])),
+        )?
+        .unwrap();
+        let mut paths = generated.sources.into_keys().collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            vec![
+                dest_dir.join("src").join("bin").join("a-lucky-seven.rs"),
+                dest_dir.join("src").join("bin").join("a.rs"),
+            ],
+            paths,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn two_character_letters() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-input-template-")
+            .tempdir()?;
+        let dest_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+        std::fs::write(
+            dest_dir.join("task.html"),
+            r#"<span class="h2">G - Gap</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>
+<span class="h2">Ex - Extra</span>
+<h3>入力</h3><pre>N M</pre><h3>出力</h3>
+<span class="h2">F2 - Hard Version</span>
+<h3>入力</h3><pre>N K</pre><h3>出力</h3>"#,
+        )?;
+
+        let generated = generate_template(
+            dest_dir,
+            &AtCoder,
+            &validated(),
+            None,
+            &mut Shell::from_read_write(Box::new(&b""[..]), Box::new(vec![])),
+        )?
+        .unwrap();
+        let mut paths = generated.sources.into_keys().collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            vec![
+                dest_dir.join("src").join("bin").join("ex.rs"),
+                dest_dir.join("src").join("bin").join("f2.rs"),
+                dest_dir.join("src").join("bin").join("g.rs"),
+            ],
+            paths,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn name_fn() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-input-template-")
+            .tempdir()?;
+        let dest_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+        std::fs::write(
+            dest_dir.join("task.html"),
+            r#"<span class="h2">A - Two Sides</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>
+<span class="h2">B - Lucky Seven</span>
+<h3>入力</h3><pre>N M</pre><h3>出力</h3>"#,
+        )?;
+
+        let name_fn = |letter: &str, _: &str| {
+            Utf8PathBuf::from(format!("tasks/{}/main.rs", letter.to_lowercase()))
+        };
+        let generated = generate_template(
+            dest_dir,
+            &AtCoder,
+            &validated(),
+            Some(&name_fn),
+            &mut Shell::from_read_write(Box::new(&b""[..]), Box::new(vec![])),
+        )?
+        .unwrap();
+        let mut paths = generated.sources.into_keys().collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            vec![
+                dest_dir.join("tasks").join("a").join("main.rs"),
+                dest_dir.join("tasks").join("b").join("main.rs"),
+            ],
+            paths,
+        );
+
+        let generate = |name_fn: &NameFn<'_>| {
+            generate_template(
+                dest_dir,
+                &AtCoder,
+                &validated(),
+                Some(name_fn),
+                &mut Shell::from_read_write(Box::new(&b""[..]), Box::new(vec![])),
+            )
+            .unwrap_err()
+            .to_string()
+        };
+        assert_eq!(
+            "A: `../a.rs` is not a relative path without `.` or `..`",
+            generate(&|letter, _| format!("../{}.rs", letter.to_lowercase()).into()),
+        );
+        assert_eq!(
+            "both A and B are named `main.rs`",
+            generate(&|_, _| "main.rs".into()),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn applied_options() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-input-template-")
+            .tempdir()?;
+        let dest_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+        std::fs::write(
+            dest_dir.join("task.html"),
+            r#"<span class="h2">A - Two Sides</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>"#,
+        )?;
+
+        let generated = generate_template(
+            dest_dir,
+            &AtCoder,
+            &TemplateOptions {
+                allow_clippy: true,
+                compact_ints: true,
+                name_with_title: true,
+                ..TemplateOptions::default()
+            },
+            None,
+            &mut Shell::from_read_write(Box::new(&b""[..]), Box::new(vec![])),
+        )?
+        .unwrap();
+        let source = &generated.sources[&dest_dir.join("src").join("bin").join("a-two-sides.rs")];
+        assert_eq!(
+            vec!["allow_clippy", "name_with_title"],
+            source.applied_options,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn task_url_comment() -> anyhow::Result<()> {
+        let options = TemplateOptions {
+            contest: Some("abc001".to_owned()),
+            task_slugs: hashmap!("A".to_owned() => "abc001_1".to_owned()),
+            ..validated()
+        };
+        let rendered = render_section(&section("A", &[&["N"]]), &options)?;
+        assert!(
+            rendered.starts_with(
+                "// https://atcoder.jp/contests/abc001/tasks/abc001_1\nuse proconio::input;\n"
+            ),
+            "{rendered}",
+        );
+        let rendered = render_section(&section("B", &[&["N"]]), &options)?;
+        assert!(!rendered.contains("https://"));
+        Ok(())
+    }
+
+    #[test]
+    fn modulus() -> anyhow::Result<()> {
+        let sections = parse_task_sections(
+            r#"<span class="h2">A - Sum</span>
+<h3>問題文</h3><p>答えを <var>998244353</var> で割った余りを出力してください。</p>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>
+<span class="h2">B - Product</span>
+<h3>問題文</h3><p>Print the answer modulo <var>10^9+7</var>.</p>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>
+<span class="h2">C - Max</span>
+<h3>制約</h3><var>A_i \le 10^9+7</var>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>"#,
+        );
+        assert_eq!(
+            vec![
+                Some(Modulus::Fixed(998_244_353)),
+                Some(Modulus::Fixed(1_000_000_007)),
+                None,
+            ],
+            sections
+                .iter()
+                .map(|s| s.modulus.clone())
+                .collect::<Vec<_>>(),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn html_schema() {
+        assert_eq!(
+            Some(HtmlSchema::SpanH2),
+            detect_schema(
+                r#"<span class="h2">A - Foo</span><h3>入力</h3><pre>N</pre><h3>出力</h3>"#
+            ),
+        );
+        assert_eq!(
+            None,
+            detect_schema(r#"<h2 class="task-title">A - Foo</h2><h4>Input</h4><pre>N</pre>"#),
+        );
+    }
+
+    #[test]
+    fn yukicoder() -> anyhow::Result<()> {
+        let sections = parse_yukicoder_sections(
+            r#"<!-- cargo-compete: index=1 -->
+<html><head><title>No.1 道のショートカット - yukicoder</title></head><body>
+<div class="block"><h5>入力</h5><pre>$N$
+$A_1$ $A_2$ $\ldots$ $A_N$</pre></div>
+<div class="sample"><h5>サンプル1</h5><h6>入力</h6><pre>3
+1 2 3
+</pre><h6>出力</h6><pre>6
+</pre></div>
+</body></html>
+"#,
+        );
+        assert_eq!(1, sections.len());
+        assert_eq!("1", sections[0].letter);
+        assert_eq!("道のショートカット", sections[0].title);
+        assert_eq!(vec!["3\n1 2 3\n".to_owned()], sections[0].samples);
+        Ok(())
+    }
+
+    #[test]
+    fn splice() {
+        let existing = r#"use proconio::input;
+
+fn main() {
+    input! {
+        n: usize,
+    }
+    let s = "}";
+    for i in 0..n {
+        if i % 2 == 0 {
+            println!("{}", s);
+        }
+    }
+}
+"#;
+        let generated = r"use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        a: [(usize, usize); n],
+    }
+}";
+        assert_eq!(
+            r#"use proconio::input;
+
+fn main() {
+    input! {
+        n: usize,
+        a: [(usize, usize); n],
+    }
+    let s = "}";
+    for i in 0..n {
+        if i % 2 == 0 {
+            println!("{}", s);
+        }
+    }
+}
+"#,
+            splice_input_block(existing, generated),
+        );
+
+        let existing = "fn main() {\n    todo!();\n}\n";
+        assert_eq!(existing, splice_input_block(existing, generated));
+    }
+
+    #[test]
+    fn parsed_cache() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-input-template-")
+            .tempdir()?;
+        let dest_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+        let html_path = dest_dir.join("task.html");
+        let cache_path = dest_dir.join("task.parsed.json");
+        let generate = || -> anyhow::Result<Vec<_>> {
+            let generated = generate_template(
+                dest_dir,
+                &AtCoder,
+                &validated(),
+                None,
+                &mut Shell::from_read_write(Box::new(&b""[..]), Box::new(vec![])),
+            )?
+            .unwrap();
+            Ok(generated.sources.into_keys().collect())
+        };
+
+        std::fs::write(
+            &html_path,
+            r#"<span class="h2">A - Foo</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>"#,
+        )?;
+        assert_eq!(
+            vec![dest_dir.join("src").join("bin").join("a.rs")],
+            generate()?
+        );
+
+        // The cache is used as long as `task.html` is the same.
+        let cache = std::fs::read_to_string(&cache_path)?;
+        std::fs::write(
+            &cache_path,
+            cache.replace(r#""letter":"A""#, r#""letter":"Z""#),
+        )?;
+        assert_eq!(
+            vec![dest_dir.join("src").join("bin").join("z.rs")],
+            generate()?
+        );
+
+        std::fs::write(
+            &html_path,
+            r#"<span class="h2">B - Bar</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>"#,
+        )?;
+        assert_eq!(
+            vec![dest_dir.join("src").join("bin").join("b.rs")],
+            generate()?
+        );
+
+        // A cache that cannot be written is only warned.
+        std::fs::remove_file(&cache_path)?;
+        std::fs::create_dir(&cache_path)?;
+        let mut warnings = Warnings::default();
+        let generated =
+            generate_template(dest_dir, &AtCoder, &validated(), None, &mut warnings)?.unwrap();
+        assert_eq!(1, generated.sources.len());
+        assert_eq!(1, warnings.0.len());
+        assert!(
+            warnings.0[0].starts_with("could not cache the parsed tasks"),
+            "{:?}",
+            warnings.0,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_format() -> anyhow::Result<()> {
+        let expected = r"use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        a: [usize; n],
+        b: [usize; n],
+    }
+}";
+        assert_eq!(
+            expected,
+            generate_from_format("$N$\n$A_1 A_2 \\ldots A_N$\n$B_1$\n$\\vdots$\n$B_N$\n")?,
+        );
+        assert_eq!(
+            expected,
+            generate_from_format("N\nA_1 A_2 ... A_N\nB_1\n:\nB_N\n")?,
+        );
+        assert!(generate_from_format("\n\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn multiple_input_headers() -> anyhow::Result<()> {
+        let sections = parse_task_sections(
+            r#"<span class="h2">A - Two Headers</span>
+<div class="part"><section><h3>入力</h3><p>入力は以下の形式で与えられる。</p>
+<pre><var>N</var> <var>Q</var>
+<var>\mathrm{query}_1</var>
+<var>\vdots</var>
+<var>\mathrm{query}_Q</var>
+</pre></section></div>
+<div class="part"><section><h3>出力</h3><pre><var>x_1</var></pre></section></div>
+<div class="part"><section><h3>入力 (クエリ)</h3><p>各クエリは以下のいずれかの形式である。</p>
+<h4>タイプ 1</h4><pre>1 <var>x</var></pre>
+<h4>タイプ 2</h4><pre>2</pre></section></div>
+<div class="part"><section><h3>入力例 1</h3><pre>1 2
+1 5
+2
+</pre></section></div>"#,
+        );
+        assert_eq!(
+            vec![
+                vec![r"N Q", r"\mathrm{query}_1", r"\vdots", r"\mathrm{query}_Q"],
+                vec!["1 x"],
+                vec!["2"],
+            ],
+            sections[0].input_blocks,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn string_length() -> anyhow::Result<()> {
+        let mut task = section("A", &[&["N", "S"]]);
+        task.statement = "S is a string. |S| = N".to_owned();
+        let rendered = render_section(&task, &validated())?;
+        assert!(rendered.contains("s: Chars, // |s| = n"), "{rendered}");
+        Ok(())
+    }
+
+    #[test]
+    fn empty_task_html() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-input-template-")
+            .tempdir()?;
+        let dest_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+        std::fs::write(dest_dir.join("task.html"), "")?;
+        let mut warnings = Warnings::default();
+        let generated = generate_template(dest_dir, &AtCoder, &validated(), None, &mut warnings)?;
+        assert!(generated.is_none());
+        assert_eq!(
+            vec![format!(
+                "`{}` is almost empty. Remove it and run again to re-download it.",
+                dest_dir.join("task.html"),
+            )],
+            warnings.0,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tuple_column_types() -> anyhow::Result<()> {
+        let mut task = section("A", &[&["N M", "u_1 v_1 c_1", r"\vdots", "u_M v_M c_M"]]);
+        task.samples = vec![
+            "3 2\n1 2 a\n2 3 b\n".to_owned(),
+            "2 1\n1 2 red\n".to_owned(),
+        ];
+        let rendered = render_section(&task, &validated())?;
+        assert!(
+            rendered.contains("uvc: [(usize, usize, String); m],"),
+            "{rendered}",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn yes_no() -> anyhow::Result<()> {
+        let options = TemplateOptions {
+            yes_no: true,
+            ..validated()
+        };
+        let mut task = section("A", &[&["N"]]);
+        task.output = "If so, print YES; otherwise, print NO.".to_owned();
+        assert!(render_section(&task, &options)?.contains(r#"if b { "YES" } else { "NO" }"#));
+
+        // Arbitrary strings, even ones mentioning `Yes` in a word.
+        task.output = "S を出力せよ。Yesterday や Notice のように出力してもよい。".to_owned();
+        assert!(!render_section(&task, &options)?.contains("fn yn("));
+
+        task.output = "条件を満たすなら Yes を、そうでないなら No を出力せよ。".to_owned();
+        assert!(!render_section(&task, &validated())?.contains("fn yn("));
+        Ok(())
+    }
+
+    #[test]
+    fn deep_recursion() -> anyhow::Result<()> {
+        let mut task = section("A", &[&["N", "u_1 v_1", r"\vdots", "u_{N-1} v_{N-1}"]]);
+        task.samples = vec!["3\n1 2\n2 3\n".to_owned()];
+        task.constraints = vec![r"2 \le N \le 2 \times 10^5".to_owned()];
+        task.statement = "N 頂点の木が与えられます。".to_owned();
+
+        let options = TemplateOptions {
+            deep_recursion_threshold: Some(1_000_000),
+            ..validated()
+        };
+        assert!(!render_section(&task, &options)?.contains("NOTE"));
+
+        task.constraints = vec![r"2 \le N \le 100".to_owned()];
+        assert!(!render_section(&task, &validated())?.contains("NOTE"));
+
+        task.constraints = vec![r"2 \le N \le 2 \times 10^5".to_owned()];
+        task.statement = "N 個の区間が与えられます。".to_owned();
+        assert!(!render_section(&task, &validated())?.contains("NOTE"));
+
+        // The edges have a note of their own without samples.
+        task.statement = "N 頂点の木が与えられます。".to_owned();
+        task.samples = vec![];
+        let rendered = render_section(&task, &validated())?;
+        assert!(rendered.contains("// NOTE: no sample"), "{rendered}");
+        assert!(rendered.contains("up to 200000 vertices"), "{rendered}");
+        Ok(())
+    }
+
+    /// `AtCoder` whose task pages are `page`.
+    struct TaskPage(Option<&'static str>);
+
+    impl Judge for TaskPage {
+        fn fetch_tasks(
+            &self,
+            _: Option<&str>,
+            _: &BTreeMap<&str, &Url>,
+            _: &Path,
+            _: &mut dyn Reporter,
+        ) -> anyhow::Result<Option<Vec<u8>>> {
+            unreachable!();
+        }
+
+        fn fetch_task(
+            &self,
+            _: &str,
+            _: &str,
+            _: &Path,
+            _: &mut dyn Reporter,
+        ) -> anyhow::Result<Option<Vec<u8>>> {
+            Ok(self.0.map(|page| page.as_bytes().to_owned()))
+        }
+
+        fn recognizes(&self, html: &str) -> bool {
+            AtCoder.recognizes(html)
+        }
+
+        fn parse_sections(&self, html: &str) -> Vec<TaskSection> {
+            AtCoder.parse_sections(html)
+        }
+
+        fn cookie_target(&self) -> Option<CookieTarget> {
+            None
+        }
+    }
+
+    #[test]
+    fn refetch_task_pages() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-input-template-")
+            .tempdir()?;
+        let dest_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+        std::fs::write(
+            dest_dir.join("task.html"),
+            r#"<span class="h2">A - Broken</span>
+<h3>入力</h3><pre>\{ a \mid b \}</pre><h3>出力</h3>"#,
+        )?;
+        let options = TemplateOptions {
+            refetch_task_pages: true,
+            contest: Some("abc001".to_owned()),
+            task_slugs: hashmap!("A".to_owned() => "abc001_1".to_owned()),
+            ..validated()
+        };
+
+        let judge = TaskPage(Some(
+            r#"<span class="h2">A - Broken</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>"#,
+        ));
+        let mut warnings = Warnings::default();
+        let generated = generate_template(dest_dir, &judge, &options, None, &mut warnings)?;
+        let source = &generated.unwrap().sources[&dest_dir.join("src").join("bin").join("a.rs")];
+        assert!(source.content.contains("        n: usize,"));
+        assert!(warnings.0.is_empty());
+
+        let mut warnings = Warnings::default();
+        generate_template(dest_dir, &TaskPage(None), &options, None, &mut warnings)?;
+        assert_eq!(
+            vec![
+                "A: no task page to read",
+                "A: `src/bin/a.rs` has 1 TODO(s) to fill in by hand"
+            ],
+            warnings
+                .0
+                .iter()
+                .map(|w| w.replace(dest_dir.as_str(), "").replace("`/", "`"))
+                .collect::<Vec<_>>(),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn environments() -> anyhow::Result<()> {
+        let sections = parse_task_sections(
+            r#"<span class="h2">A - Aligned</span>
+<h3>入力</h3><pre>
+\begin{array}{ll}
+N &amp; M \\
+A_1 &amp; A_2 &amp; \ldots &amp; A_N \\ B_1 &amp; B_2 &amp; \ldots &amp; B_M
+\end{array}
+</pre>
+<h3>出力</h3>
+<span class="h2">B - Cases</span>
+<h3>入力</h3><pre>
+N
+\begin{cases}
+x_1 \\
+x_2
+\end{cases}
+</pre>
+<h3>出力</h3>"#,
+        );
+        assert_eq!(
+            vec![vec!["N M", r"A_1 A_2 \ldots A_N", r"B_1 B_2 \ldots B_M",]],
+            sections[0].input_blocks,
+        );
+        assert_eq!(vec![vec!["N", "x_1", "x_2"]], sections[1].input_blocks);
+        Ok(())
+    }
+
+    #[test]
+    fn letters() -> anyhow::Result<()> {
+        assert!(letter_matches("d", "D"));
+        assert!(letter_matches("EX", "Ex"));
+        assert!(!letter_matches("E", "Ex"));
+        assert!(letter_matches("D-F", "E"));
+        assert!(letter_matches("d-f", "Ex"));
+        assert!(letter_matches("D-F", "F2"));
+        assert!(!letter_matches("D-F", "C"));
+        assert!(!letter_matches("D-F", "G"));
+
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-input-template-")
+            .tempdir()?;
+        let dest_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+        std::fs::write(
+            dest_dir.join("task.html"),
+            r#"<span class="h2">C - Third</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>
+<span class="h2">D - Fourth</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>
+<span class="h2">Ex - Extra</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>"#,
+        )?;
+        let mut warnings = Warnings::default();
+        let generated = generate_template(
+            dest_dir,
+            &AtCoder,
+            &TemplateOptions {
+                letters: Some(vec!["d".to_owned(), "ex".to_owned()]),
+                ..validated()
+            },
+            None,
+            &mut warnings,
+        )?
+        .unwrap();
+        let mut paths = generated.sources.into_keys().collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            vec![
+                dest_dir.join("src").join("bin").join("d.rs"),
+                dest_dir.join("src").join("bin").join("ex.rs"),
+            ],
+            paths,
+        );
+        assert!(warnings.0.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn print_array() -> anyhow::Result<()> {
+        let options = TemplateOptions {
+            print_array: true,
+            ..validated()
+        };
+        let mut task = section("A", &[&["N"]]);
+        task.output = "Print x_1 x_2 ... x_N in this order.".to_owned();
+        assert!(render_section(&task, &options)?.contains("fn print_array<"));
+
+        task.output = "答えを出力せよ。".to_owned();
+        assert!(!render_section(&task, &options)?.contains("print_array"));
+        task.output = "Print the minimum of A_i.".to_owned();
+        assert!(!render_section(&task, &options)?.contains("print_array"));
+        Ok(())
+    }
+
+    #[test]
+    fn string_array() -> anyhow::Result<()> {
+        let mut words = section("A", &[&["N", r"W_1 W_2 \ldots W_N"]]);
+        words.samples = vec!["3\nabc de f\n".to_owned()];
+        assert!(render_section(&words, &validated())?.contains("w: [Chars; n],"));
+
+        let mut task = section("A", &[&["N", r"S_1 S_2 \ldots S_N"]]);
+        task.samples = vec!["3\n1 2 3\n".to_owned()];
+        assert!(render_section(&task, &validated())?.contains("s: [usize; n],"));
+
+        let task = section("A", &[&["N", r"X_1 X_2 \ldots X_N"]]);
+        assert!(render_section(&task, &validated())?.contains("x: [usize; n],"));
+        Ok(())
+    }
+
+    #[test]
+    fn grids_not_named_s() -> anyhow::Result<()> {
+        let expected = r"use proconio::{input, marker::Chars};
+fn main() {
+    input! {
+        h: usize,
+        w: usize,
+        g: [Chars; h],
+    }
+}";
+        let mut task = section("A", &[&["H W", "G_1", r"\vdots", "G_H"]]);
+        assert_eq!(expected, render_section(&task, &validated())?);
+        task.samples = vec!["2 3\nABC\nDEF\n".to_string()];
+        assert_eq!(expected, render_section(&task, &validated())?);
+
+        // Numbers separated by spaces make a matrix.
+        let mut task = section("A", &[&["H W", "C_1", r"\vdots", "C_H"]]);
+        task.samples = vec!["2 3\n1 -2 3\n4 5 6\n".to_string()];
+        assert!(render_section(&task, &validated())?.contains("c: [[i64; w]; h],"));
+
+        // One number per row is a column of numbers.
+        task.samples = vec!["2 3\n10\n20\n".to_string()];
+        assert!(render_section(&task, &validated())?.contains("c: [usize; h],"));
+
+        // Without the width, or when the rows do not run to `H`, it is not a grid.
+        let task = section("A", &[&["H", "G_1", r"\vdots", "G_H"]]);
+        assert!(render_section(&task, &validated())?.contains("g: [usize; h],"));
+        let task = section("A", &[&["H W N", "G_1", r"\vdots", "G_N"]]);
+        assert!(render_section(&task, &validated())?.contains("g: [usize; n],"));
+        Ok(())
+    }
+
+    #[test]
+    fn bin_targets() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-input-template-")
+            .tempdir()?;
+        let dest_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+        std::fs::write(
+            dest_dir.join("task.html"),
+            r#"<span class="h2">A - Two Sides</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>
+<span class="h2">Ex - "Quoted"</span>
+<h3>入力</h3><pre>N M</pre><h3>出力</h3>"#,
+        )?;
+        std::fs::write(
+            dest_dir.join("Cargo.toml"),
+            "[package]\nname = \"abc001\"\nversion = \"0.1.0\"\n",
+        )?;
+        let options = TemplateOptions {
+            bin_targets: true,
+            name_with_title: true,
+            ..validated()
+        };
+        let generated = generate_template(
+            dest_dir,
+            &AtCoder,
+            &options,
+            None,
+            &mut Shell::from_read_write(Box::new(&b""[..]), Box::new(vec![])),
+        )?
+        .unwrap();
+        let snippets = generated
+            .bin_targets
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "[[bin]]\nname = \"abc001-a\"\npath = \"src/bin/a-two-sides.rs\"\n",
+                "[[bin]]\nname = \"abc001-ex\"\npath = \"src/bin/ex-quoted.rs\"\n",
+            ],
+            snippets,
+        );
+        let manifest = snippets.concat().parse::<toml::Value>()?;
+        assert_eq!(2, manifest["bin"].as_array().unwrap().len());
+
+        let generated = generate_template(
+            dest_dir,
+            &AtCoder,
+            &validated(),
+            None,
+            &mut Shell::from_read_write(Box::new(&b""[..]), Box::new(vec![])),
+        )?
+        .unwrap();
+        assert!(generated.bin_targets.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn modulus_read_from_input() -> anyhow::Result<()> {
+        let sections = parse_task_sections(
+            r#"<span class="h2">A - Power</span>
+<h3>問題文</h3><p>答えを <var>P</var> で割った余りを出力してください。</p>
+<h3>制約</h3><var>2 \le P \le 10^9+7</var>
+<h3>入力</h3><pre>N P</pre><h3>出力</h3>
+<span class="h2">B - Sum</span>
+<h3>問題文</h3><p>Print the sum modulo <var>M</var>, where <var>M = 998244353</var>.</p>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>"#,
+        );
+        assert_eq!(
+            vec![
+                Some(Modulus::Input("p".to_owned())),
+                Some(Modulus::Fixed(998_244_353)),
+            ],
+            sections
+                .iter()
+                .map(|s| s.modulus.clone())
+                .collect::<Vec<_>>(),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn index_range_arrays() -> anyhow::Result<()> {
+        // Without the range, it is a scalar.
+        let mut task = section("A", &[&["N", "A_i"]]);
+        task.constraints = vec![r"1 \le N \le 10^5".to_owned()];
+        assert!(render_section(&task, &validated())?.contains("a_i: usize,"));
+        Ok(())
+    }
+
+    #[test]
+    fn generated_order() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-input-template-")
+            .tempdir()?;
+        let dest_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+        std::fs::write(
+            dest_dir.join("task.html"),
+            r#"<span class="h2">Z - Last</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>
+<span class="h2">B - Second</span>
+<h3>入力</h3><pre>N M</pre><h3>出力</h3>
+<span class="h2">A - First</span>
+<h3>入力</h3><pre>N K</pre><h3>出力</h3>"#,
+        )?;
+        let generated = generate_template(
+            dest_dir,
+            &AtCoder,
+            &validated(),
+            None,
+            &mut Shell::from_read_write(Box::new(&b""[..]), Box::new(vec![])),
+        )?
+        .unwrap();
+        let src_dir = dest_dir.join("src").join("bin");
+        assert_eq!(
+            vec![
+                src_dir.join("z.rs"),
+                src_dir.join("b.rs"),
+                src_dir.join("a.rs")
+            ],
+            generated.order,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn range_query_hint() -> anyhow::Result<()> {
+        let task = section(
+            "A",
+            &[
+                &["N Q", r"\mathrm{query}_1", r"\vdots", r"\mathrm{query}_Q"],
+                &["1 p x"],
+                &["2 l r"],
+            ],
+        );
+        let options = TemplateOptions {
+            range_query_hint: RangeQueryHint::AcLibrary,
+            ..validated()
+        };
+        assert_eq!(
+            r"use proconio::input;
+use ac_library::FenwickTree;
+// NOTE: point updates and range queries. A Fenwick tree or a segment tree fits.
+fn main() {
+    input! {
+        n: usize,
+        q: usize,
+    }
+    let mut bit = FenwickTree::new(n, 0_u64);
+    for _ in 0..q {
+        input! { qt: usize }
+        match qt {
+            1 => { input! { p: usize, x: usize } },
+            _ => { input! { l: usize, r: usize } }, // 2
+        }
+        /* TODO: process query */
+    }
+}",
+            render_section(&task, &options)?,
+        );
+
+        let options = TemplateOptions {
+            range_query_hint: RangeQueryHint::Comment,
+            ..validated()
+        };
+        let rendered = render_section(&task, &options)?;
+        assert!(rendered.contains("// NOTE: point updates and range queries."));
+        assert!(!rendered.contains("FenwickTree"));
+
+        // Not a range.
+        let task = section(
+            "A",
+            &[
+                &["N Q", r"\mathrm{query}_1", r"\vdots", r"\mathrm{query}_Q"],
+                &["1 p x"],
+                &["2 u v"],
+            ],
+        );
+        assert!(!render_section(&task, &options)?.contains("NOTE"));
+        Ok(())
+    }
+
+    #[test]
+    fn bin_dir() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-input-template-")
+            .tempdir()?;
+        let dest_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+        std::fs::write(
+            dest_dir.join("task.html"),
+            r#"<span class="h2">A - First</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>"#,
+        )?;
+        let generate = |bin_dir: &str, warnings: &mut Warnings| {
+            let options = TemplateOptions {
+                bin_dir: Some(bin_dir.into()),
+                ..validated()
+            };
+            generate_template(dest_dir, &AtCoder, &options, None, warnings)
+                .map(|generated| generated.unwrap().order)
+        };
+
+        let mut warnings = Warnings::default();
+        assert_eq!(
+            vec![dest_dir.join("src").join("a.rs")],
+            generate("src", &mut warnings)?
+        );
+        assert_eq!(
+            vec![dest_dir.join("tasks").join("abc").join("a.rs")],
+            generate("tasks/abc", &mut warnings)?,
+        );
+        assert!(warnings.0.is_empty());
+
+        assert_eq!(
+            vec![dest_dir.join("src").join("bin").join("a.rs")],
+            generate("../elsewhere", &mut warnings)?,
+        );
+        assert_eq!(1, warnings.0.len());
+        Ok(())
+    }
+
+    #[test]
+    fn points() {
+        let sections = parse_task_sections(
+            r#"<span class="h2">A - First</span>
+<span class="lang-ja"><p>配点 : <var>100</var> 点</p></span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>
+<span class="h2">B - Second</span>
+<span class="lang-en"><p>Score : <var>250</var> points</p></span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>
+<span class="h2">C - Third</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>"#,
+        );
+        assert_eq!(
+            vec![Some(100), Some(250), None],
+            sections.iter().map(|s| s.points).collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn digit_grid() -> anyhow::Result<()> {
+        let options = TemplateOptions {
+            digit_grid: true,
+            ..validated()
+        };
+        let mut task = section("A", &[&["H W", "A_1", r"\vdots", "A_H"]]);
+
+        // Numbers that are not as long as the width, and letters, are not digits.
+        task.samples = vec!["2 3\n10\n20\n".to_string()];
+        assert!(render_section(&task, &options)?.contains("a: [usize; h],"));
+        task.samples = vec!["2 3\nabc\ndef\n".to_string()];
+        assert!(!render_section(&task, &options)?.contains("to_digit"));
+        Ok(())
+    }
+
+    #[test]
+    fn panic_in_render() {
+        assert_eq!(
+            "panicked (no captures)",
+            catch_render(|| panic!("no captures"))
+                .unwrap_err()
+                .to_string(),
+        );
+        let n = 3;
+        assert_eq!(
+            "panicked (index out of range: 3)",
+            catch_render(|| panic!("index out of range: {n}"))
+                .unwrap_err()
+                .to_string(),
+        );
+        assert_eq!("ok", catch_render(|| Ok("ok".to_owned())).unwrap());
+    }
+
+    #[test]
+    fn manual_reader() -> anyhow::Result<()> {
+        let options = TemplateOptions {
+            reader: Reader::Manual,
+            ..validated()
+        };
+        let task = section(
+            "A",
+            &[&[
+                "N M",
+                r"A_1 A_2 \ldots A_N",
+                "S",
+                "u_1 v_1",
+                r"\vdots",
+                "u_M v_M",
+            ]],
+        );
+        let rendered = render_section(&task, &options)?;
+        assert!(!rendered.contains("proconio"), "{rendered}");
+        assert!(!rendered.contains("input!"), "{rendered}");
+        assert!(
+            rendered.starts_with(
+                r"fn main() {
+    let mut sc = Scanner::default();
+    let n = sc.next::<usize>();
+    let m = sc.next::<usize>();
+    let a = (0..n).map(|_| sc.next::<usize>()).collect::<Vec<_>>();
+    let s = sc.next::<String>().chars().collect::<Vec<_>>();
+"
+            ),
+            "{rendered}",
+        );
+        assert!(rendered.contains("struct Scanner {"));
+        Ok(())
+    }
+
+    #[test]
+    fn manual_expr() {
+        assert_eq!(
+            "(0..h).map(|_| (0..w).map(|_| sc.next::<u64>()).collect::<Vec<_>>()).collect::<Vec<_>>()",
+            super::manual_expr("[[u64; w]; h]"),
+        );
+        assert_eq!(
+            "(0..m).map(|_| (sc.next::<usize>() - 1, sc.next::<i64>())).collect::<Vec<_>>()",
+            super::manual_expr("[(Usize1, i64); m]"),
+        );
+        assert_eq!(
+            "{ let len = sc.next::<usize>(); (0..len).map(|_| sc.next::<usize>()).collect::<Vec<_>>() }",
+            super::manual_expr("[usize]"),
+        );
+        assert_eq!(
+            "let x = sc.next::<usize>(); let mut y = sc.next::<String>().into_bytes();",
+            ["x: usize", "mut y: Bytes"]
+                .map(super::manual_read)
+                .join(" "),
+        );
+    }
+
+    #[test]
+    fn case_answers() -> anyhow::Result<()> {
+        let mut task = section(
+            "A",
+            &[
+                &["T", r"\mathrm{case}_1", r"\vdots", r"\mathrm{case}_T"],
+                &["N"],
+            ],
+        );
+        task.output = "Print the answer.".to_owned();
+        let options = TemplateOptions {
+            batch_output: true,
+            ..validated()
+        };
+        assert!(!render_section(&task, &options)?.contains("ans"));
+        Ok(())
+    }
+
+    #[test]
+    fn fastout() -> anyhow::Result<()> {
+        let options = TemplateOptions {
+            fastout: true,
+            ..validated()
+        };
+        assert!(render_section(
+            &section("A", &[&["H W", "S_1", r"\vdots", "S_H"]]),
+            &options
+        )?
+        .starts_with("use proconio::{fastout, input, marker::Chars};\n#[fastout]\nfn main() {"));
+
+        let options = TemplateOptions {
+            wrap_in_module: true,
+            ..options
+        };
+        assert!(render_section(&section("A", &[&["N"]]), &options)?
+            .contains("    #[fastout]\n    pub fn main() {"));
+        Ok(())
+    }
+
+    #[test]
+    fn signed_coordinates() -> anyhow::Result<()> {
+        let mut task = section("A", &[&["N", "X_1 Y_1", r"\vdots", "X_N Y_N"]]);
+        task.constraints = vec![
+            r"1 \le N \le 10^5".to_owned(),
+            r"-10^9 \le X_i, Y_i \le 10^9".to_owned(),
+        ];
+        task.samples = vec!["2\n1 2\n3 4\n".to_owned()];
+        assert!(render_section(&task, &validated())?.contains("xy: [(i64, i64); n],\n"));
+
+        // Only the column that may be negative.
+        task.constraints = vec![
+            r"1 \le X_i \le 10^9".to_owned(),
+            r"-10^9 \le Y_i \le 10^9".to_owned(),
+        ];
+        assert!(render_section(&task, &validated())?.contains("xy: [(usize, i64); n],\n"));
+        task.constraints = vec![];
+        task.samples = vec!["2\n1 -2\n3 4\n".to_owned()];
+        assert!(render_section(&task, &validated())?.contains("xy: [(usize, i64); n],\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn creates_src_dir() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-input-template-")
+            .tempdir()?;
+        let dest_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+        std::fs::write(
+            dest_dir.join("task.html"),
+            r#"<span class="h2">A - First</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>"#,
+        )?;
+        let mut shell = Shell::from_read_write(Box::new(&b""[..]), Box::new(vec![]));
+        generate_template(dest_dir, &AtCoder, &validated(), None, &mut shell)?;
+        assert!(dest_dir.join("src").join("bin").is_dir());
+        // Once more, with the directory there.
+        generate_template(dest_dir, &AtCoder, &validated(), None, &mut shell)?;
+
+        std::fs::remove_dir_all(dest_dir.join("src"))?;
+        std::fs::write(dest_dir.join("src"), "")?;
+        let err =
+            generate_template(dest_dir, &AtCoder, &validated(), None, &mut shell).unwrap_err();
+        assert_eq!(
+            format!("could not create `{}`", dest_dir.join("src").join("bin")),
+            err.to_string(),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn query_type_not_first() -> anyhow::Result<()> {
+        let task = section(
+            "A",
+            &[
+                &["N Q", r"\mathrm{query}_1", r"\vdots", r"\mathrm{query}_Q"],
+                &["k 1 x"],
+                &["k 2 l r"],
+            ],
+        );
+        let rendered = render_section(&task, &validated())?;
+        assert!(
+            rendered.contains(
+                "        input! { k: usize, qt: usize }
+        match qt {
+            1 => { input! { x: usize } },
+            _ => { input! { l: usize, r: usize } }, // 2
+        }"
+            ),
+            "{rendered}",
+        );
+
+        // Not read after the same fields.
+        let task = section(
+            "A",
+            &[
+                &["N Q", r"\mathrm{query}_1", r"\vdots", r"\mathrm{query}_Q"],
+                &["x 1"],
+                &["y 2"],
+            ],
+        );
+        let rendered = render_section(&task, &validated())?;
+        assert!(rendered.contains("input! { qt: usize }"), "{rendered}");
+        Ok(())
+    }
+
+    #[test]
+    fn cases_in_prose() -> anyhow::Result<()> {
+        let mut task = section("A", &[&["T"], &["N", r"A_1 A_2 \ldots A_N"]]);
+        task.statement = "The first line contains the number of test cases T.".to_owned();
+        assert!(render_section(&task, &validated())?.contains("for _ in 0..t {"));
+
+        task.statement = "T 個の整数が与えられる。".to_owned();
+        assert!(!render_section(&task, &validated())?.contains("for _ in 0..t {"));
+        Ok(())
+    }
+
+    #[cfg(feature = "validate-syntax")]
+    #[test]
+    fn validate_syntax() {
+        assert!(super::validate_syntax(
+            "use proconio::input;\nfn main() {\n    input! { n: usize }\n}"
+        )
+        .is_ok());
+        let err =
+            super::validate_syntax("fn main() {\n    input! {\n        n: usize,\n}").unwrap_err();
+        assert_eq!("the generated code does not parse", err.to_string());
+    }
 }
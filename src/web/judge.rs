@@ -0,0 +1,157 @@
+use crate::{
+    shell::Reporter,
+    web::{
+        cookie_atcoder_py::CookieTarget,
+        input_template::{
+            detect_schema, parse_task_sections, parse_yukicoder_sections, TaskSection,
+        },
+        tasks_print_html::{fetch_atcoder_task_page, fetch_atcoder_tasks_print, offline},
+        yukicoder_problem_html::{fetch_yukicoder_problems, INDEX_MARKER},
+    },
+};
+use anyhow::Context as _;
+use camino::Utf8Path;
+use std::{collections::BTreeMap, path::Path};
+use url::Url;
+
+/// The site-specific part of the input templates: where `task.html` comes from and how the tasks
+/// are laid out in it.
+pub(crate) trait Judge {
+    /// Downloads the content of `task.html`. `None` means there is nothing worth saving.
+    fn fetch_tasks(
+        &self,
+        contest: Option<&str>,
+        problems: &BTreeMap<&str, &Url>,
+        cookies_path: &Path,
+        shell: &mut dyn Reporter,
+    ) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// Downloads the page of the task `slug` alone, for a task whose format `task.html` lacks.
+    /// `None` means there is no such page to read.
+    fn fetch_task(
+        &self,
+        contest: &str,
+        slug: &str,
+        cookies_path: &Path,
+        shell: &mut dyn Reporter,
+    ) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// Whether `html` looks like a page `parse_sections` was written against.
+    fn recognizes(&self, html: &str) -> bool;
+
+    fn parse_sections(&self, html: &str) -> Vec<TaskSection>;
+
+    /// The session cookie to copy from the browser, if the judge needs one.
+    fn cookie_target(&self) -> Option<CookieTarget>;
+}
+
+/// `task.html` is `tasks_print` of the contest.
+pub(crate) struct AtCoder;
+
+impl Judge for AtCoder {
+    fn fetch_tasks(
+        &self,
+        contest: Option<&str>,
+        _: &BTreeMap<&str, &Url>,
+        cookies_path: &Path,
+        shell: &mut dyn Reporter,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let contest = contest.with_context(|| "`contest` is required for AtCoder")?;
+        fetch_atcoder_tasks_print(contest, cookies_path, shell)
+    }
+
+    fn fetch_task(
+        &self,
+        contest: &str,
+        slug: &str,
+        cookies_path: &Path,
+        shell: &mut dyn Reporter,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        fetch_atcoder_task_page(contest, slug, cookies_path, shell)
+    }
+
+    fn recognizes(&self, html: &str) -> bool {
+        detect_schema(html).is_some()
+    }
+
+    fn parse_sections(&self, html: &str) -> Vec<TaskSection> {
+        parse_task_sections(html)
+    }
+
+    fn cookie_target(&self) -> Option<CookieTarget> {
+        Some(CookieTarget::ATCODER)
+    }
+}
+
+/// `task.html` is the problem pages, each of which follows `INDEX_MARKER`.
+pub(crate) struct Yukicoder;
+
+impl Judge for Yukicoder {
+    fn fetch_tasks(
+        &self,
+        _: Option<&str>,
+        problems: &BTreeMap<&str, &Url>,
+        _: &Path,
+        shell: &mut dyn Reporter,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        fetch_yukicoder_problems(problems, shell).map(Some)
+    }
+
+    /// `task.html` already has the problem pages as they are.
+    fn fetch_task(
+        &self,
+        _: &str,
+        _: &str,
+        _: &Path,
+        _: &mut dyn Reporter,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    fn recognizes(&self, html: &str) -> bool {
+        html.contains(INDEX_MARKER) && html.contains("入力")
+    }
+
+    fn parse_sections(&self, html: &str) -> Vec<TaskSection> {
+        parse_yukicoder_sections(html)
+    }
+
+    fn cookie_target(&self) -> Option<CookieTarget> {
+        None
+    }
+}
+
+/// Saves `task.html` in `dest_dir` unless it is already there, or `ACCC_OFFLINE` is set. Failures
+/// are only warned.
+pub(crate) fn save_tasks_if_missing(
+    judge: &dyn Judge,
+    contest: Option<&str>,
+    problems: &BTreeMap<&str, &Url>,
+    dest_dir: &Utf8Path,
+    cookies_path: &Path,
+    shell: &mut dyn Reporter,
+) -> anyhow::Result<()> {
+    let dest_path = dest_dir.join("task.html");
+    if dest_path.exists() {
+        return Ok(());
+    }
+    if offline() {
+        shell.warn(format!(
+            "`{dest_path}` is missing, and not downloaded since `ACCC_OFFLINE` is set. Put it there \
+             to generate the templates.",
+        ))?;
+        return Ok(());
+    }
+
+    crate::fs::create_dir_all(dest_dir)?;
+
+    match judge.fetch_tasks(contest, problems, cookies_path, shell) {
+        Ok(Some(body)) => {
+            crate::fs::write_atomic(&dest_path, body)?;
+            shell.status("Wrote", dest_path.as_str())?;
+        }
+        Ok(None) => {}
+        Err(err) => shell.warn(format!("Failed to save `{dest_path}` ({err:#})."))?,
+    }
+    Ok(())
+}
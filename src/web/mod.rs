@@ -1,9 +1,11 @@
 pub(crate) mod cookie_atcoder_py;
 pub(crate) mod credentials;
 pub(crate) mod input_template;
+pub(crate) mod judge;
 pub(crate) mod retrieve_testcases;
 pub(crate) mod tasks_print_html;
 pub(crate) mod url;
+pub(crate) mod yukicoder_problem_html;
 
 use std::time::Duration;
 
@@ -0,0 +1,32 @@
+use crate::{
+    shell::Reporter,
+    web::tasks_print_html::{http_client, read_capped, send, MAX_BODY_BYTES},
+};
+use anyhow::Context as _;
+use std::collections::BTreeMap;
+use url::Url;
+
+/// Put before each problem page in `task.html`, since yukicoder has no `tasks_print`.
+pub(crate) const INDEX_MARKER: &str = "<!-- cargo-compete: index=";
+
+/// Downloads the pages of `problems` (as `index => URL`), each following `INDEX_MARKER`.
+pub(crate) fn fetch_yukicoder_problems(
+    problems: &BTreeMap<&str, &Url>,
+    shell: &mut dyn Reporter,
+) -> anyhow::Result<Vec<u8>> {
+    let client = http_client(shell)?;
+
+    let mut content = vec![];
+    for (index, url) in problems {
+        shell.status("Downloading", format!("`{}`", url))?;
+        let body = (|| {
+            let resp = send(client.get((*url).clone()), shell)?.error_for_status()?;
+            read_capped(resp, MAX_BODY_BYTES)
+        })()
+        .with_context(|| format!("could not download `{url}`"))?;
+        content.extend_from_slice(format!("{INDEX_MARKER}{index} -->\n").as_bytes());
+        content.extend_from_slice(&body);
+        content.push(b'\n');
+    }
+    Ok(content)
+}
@@ -0,0 +1,7 @@
+#![allow(clippy::all)]
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+    }
+}
@@ -0,0 +1,8 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        a: [i64; n],
+        b: [f64; n],
+    }
+}
@@ -0,0 +1,17 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        m: usize,
+    }
+    for _ in 0..n {
+        input! {
+            x: usize,
+            y: usize,
+            a: [usize; m],
+        }
+    }
+    input! {
+        q: usize,
+    }
+}
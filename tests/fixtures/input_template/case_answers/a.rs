@@ -0,0 +1,14 @@
+use proconio::input;
+fn main() {
+    input! {
+        t: usize,
+    }
+    for _ in 0..t {
+        input! {
+            n: usize,
+        }
+        /* TODO: solve testcase */
+        let ans: usize = todo!(); // TODO: the answer of the testcase
+        println!("{ans}");
+    }
+}
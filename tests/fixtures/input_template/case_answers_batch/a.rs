@@ -0,0 +1,17 @@
+use proconio::input;
+use std::io::{BufWriter, Write as _};
+fn main() {
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    input! {
+        t: usize,
+    }
+    for _ in 0..t {
+        input! {
+            n: usize,
+        }
+        /* TODO: solve testcase */
+        let ans: usize = todo!(); // TODO: the answer of the testcase
+        writeln!(out, "{ans}").unwrap();
+    }
+    out.flush().unwrap();
+}
@@ -0,0 +1,13 @@
+use proconio::input;
+fn main() {
+    input! {
+        t: usize,
+    }
+    for _ in 0..t {
+        input! {
+            n: usize,
+            a: [usize; n],
+        }
+        /* TODO: solve testcase */
+    }
+}
@@ -0,0 +1,28 @@
+use proconio::input;
+fn main() {
+    input! {
+        t: usize,
+    }
+    for _ in 0..t {
+        input! {
+            n: usize,
+            a: [usize; n],
+        }
+        /* TODO: solve testcase */
+        let ans: usize = todo!(); // TODO: the answer of the testcase
+        println!("{ans}");
+    }
+    // yn(todo!()); // TODO: decide the answer
+    // print_array::<usize>(todo!()); // TODO: the answer
+}
+
+#[allow(dead_code)]
+fn yn(b: bool) {
+    println!("{}", if b { "Yes" } else { "No" });
+}
+
+#[allow(dead_code)]
+fn print_array<T: std::fmt::Display>(a: &[T]) {
+    let a = a.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+    println!("{}", a.join(" "));
+}
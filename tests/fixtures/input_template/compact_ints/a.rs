@@ -0,0 +1,8 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        a: [u32; n],
+        b: [usize; n],
+    }
+}
@@ -0,0 +1,8 @@
+use proconio::input;
+// NOTE: up to 200000 vertices. A recursive DFS may overflow the stack, so use an explicit stack or run it in a thread with a larger stack.
+fn main() {
+    input! {
+        n: usize,
+        uv: [(usize, usize); n-1],
+    }
+}
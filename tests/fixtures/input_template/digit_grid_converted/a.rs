@@ -0,0 +1,9 @@
+use proconio::{input, marker::Chars};
+fn main() {
+    input! {
+        h: usize,
+        w: usize,
+        a: [Chars; h],
+    }
+    let a: Vec<Vec<usize>> = a.iter().map(|r| r.iter().map(|&c| c.to_digit(10).unwrap() as usize).collect()).collect(); // [[usize; w]; h]
+}
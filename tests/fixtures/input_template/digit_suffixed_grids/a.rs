@@ -0,0 +1,9 @@
+use proconio::{input, marker::Chars};
+fn main() {
+    input! {
+        h: usize,
+        w: usize,
+        s1: [Chars; h],
+        s2: [Chars; h],
+    }
+}
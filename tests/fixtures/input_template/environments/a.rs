@@ -0,0 +1,9 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        m: usize,
+        a: [usize; n],
+        b: [usize; m],
+    }
+}
@@ -0,0 +1,7 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        x: [usize; 2],
+    }
+}
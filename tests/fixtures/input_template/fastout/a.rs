@@ -0,0 +1,7 @@
+use proconio::{fastout, input};
+#[fastout]
+fn main() {
+    input! {
+        n: usize,
+    }
+}
@@ -0,0 +1,10 @@
+use proconio::input;
+fn main() {
+    // format:
+    //   N
+    //   A_1 A_2 \ldots A_N
+    input! {
+        n: usize,
+        a: [usize; n],
+    }
+}
@@ -0,0 +1,8 @@
+use proconio::{input, marker::Chars};
+fn main() {
+    input! {
+        h: usize,
+        w: usize,
+        s: [Chars; h],
+    }
+}
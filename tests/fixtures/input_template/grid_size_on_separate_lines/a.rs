@@ -0,0 +1,9 @@
+use proconio::{input, marker::Chars};
+fn main() {
+    input! {
+        h: usize,
+        w: usize,
+        s: [Chars; h],
+    }
+    let s: Vec<Vec<bool>> = s.iter().map(|r| r.iter().map(|&c| c == '#').collect()).collect(); // [[bool; w]; h]
+}
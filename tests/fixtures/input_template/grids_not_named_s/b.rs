@@ -0,0 +1,8 @@
+use proconio::input;
+fn main() {
+    input! {
+        h: usize,
+        w: usize,
+        c: [[i64; w]; h],
+    }
+}
@@ -0,0 +1,7 @@
+use proconio::input;
+fn main() {
+    // NOTE: the input format is only given as an image.
+    input! {
+        /* TODO: fill in by hand */
+    }
+}
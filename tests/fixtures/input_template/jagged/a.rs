@@ -0,0 +1,15 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        m: usize,
+    }
+    let mut a = Vec::with_capacity(n);
+    for _ in 0..n {
+        input! { k: usize, row: [usize; k] }
+        a.push(row);
+    }
+    input! {
+        b: [usize; m],
+    }
+}
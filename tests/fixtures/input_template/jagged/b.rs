@@ -0,0 +1,12 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        l: [usize; n],
+    }
+    let mut a = Vec::with_capacity(n);
+    for &len in &l {
+        input! { row: [usize; len] }
+        a.push(row);
+    }
+}
@@ -0,0 +1,12 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+    }
+    let mut a = Vec::with_capacity(n);
+    for _ in 0..n {
+        // TODO: read a row like `A_{1,1} \ldots A_{1,C_1}`
+        input! { row: [usize; 0] }
+        a.push(row);
+    }
+}
@@ -0,0 +1,10 @@
+use proconio::{input, marker::Chars};
+fn main() {
+    input! {
+        n: usize,
+        m: usize,
+        a: [usize; n],
+        b: [usize; m],
+        s: Chars,
+    }
+}
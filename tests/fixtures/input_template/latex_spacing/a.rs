@@ -0,0 +1,11 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        m: usize,
+        a: [usize; n],
+        b: [usize; n],
+        c: [usize; m],
+        d: [usize; m],
+    }
+}
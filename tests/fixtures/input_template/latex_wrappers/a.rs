@@ -0,0 +1,9 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        k: usize,
+        cost: [usize; n],
+        len: usize,
+    }
+}
@@ -0,0 +1,14 @@
+use proconio::input;
+fn main() {
+    input! {
+        q: usize,
+    }
+    for _ in 0..q {
+        input! { qt: usize }
+        match qt {
+            1 => { input! { type_i: usize, x_i: usize } },
+            _ => { input! { kind: usize } }, // 2
+        }
+        /* TODO: process query */
+    }
+}
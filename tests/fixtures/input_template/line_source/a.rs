@@ -0,0 +1,18 @@
+use proconio::{input, source::line::LineSource};
+use std::io::{stdin, BufReader};
+fn main() {
+    let mut source = LineSource::new(BufReader::new(stdin().lock()));
+    input! {
+        from &mut source,
+        n: usize,
+        q: usize,
+    }
+    for _ in 0..q {
+        input! { from &mut source, qt: usize }
+        match qt {
+            1 => { input! { from &mut source, x: usize } },
+            _ => {}, // 2
+        }
+        /* TODO: process query */
+    }
+}
@@ -0,0 +1,14 @@
+use proconio::{input, marker::Chars};
+fn main() {
+    input! {
+        h: usize,
+        w: usize,
+        s: [Chars; h],
+    }
+    // let s: Vec<Vec<bool>> = s.iter().map(|r| r.iter().map(|&c| c == '#').collect()).collect();
+    yn(todo!()); // TODO: decide the answer
+}
+
+fn yn(b: bool) {
+    println!("{}", if b { "Yes" } else { "No" });
+}
@@ -0,0 +1,8 @@
+use proconio::input;
+// NOTE: print the answer reduced modulo `MOD`.
+const MOD: u64 = 998244353;
+fn main() {
+    input! {
+        n: usize,
+    }
+}
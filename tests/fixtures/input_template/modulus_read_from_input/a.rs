@@ -0,0 +1,8 @@
+use proconio::input;
+// NOTE: modulus read as `p`. Print the answer reduced modulo it.
+fn main() {
+    input! {
+        n: usize,
+        p: usize,
+    }
+}
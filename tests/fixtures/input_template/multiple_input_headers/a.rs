@@ -0,0 +1,15 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        q: usize,
+    }
+    for _ in 0..q {
+        input! { qt: usize }
+        match qt {
+            1 => { input! { x: usize } },
+            _ => {}, // 2
+        }
+        /* TODO: process query */
+    }
+}
@@ -0,0 +1,8 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        p: [usize; n],
+    }
+    // NOTE: p_i are 1-indexed
+}
@@ -0,0 +1,8 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        p: [usize; n],
+    }
+    let p = p.iter().map(|&x| x - 1).collect::<Vec<_>>();
+}
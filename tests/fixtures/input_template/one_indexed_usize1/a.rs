@@ -0,0 +1,7 @@
+use proconio::{input, marker::Usize1};
+fn main() {
+    input! {
+        n: usize,
+        p: [Usize1; n],
+    }
+}
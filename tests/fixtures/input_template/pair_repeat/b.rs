@@ -0,0 +1,6 @@
+use proconio::input;
+fn main() {
+    input! {
+        ab: [(usize, usize); 3], // NOTE: no sample to check the column types
+    }
+}
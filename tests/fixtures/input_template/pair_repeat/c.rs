@@ -0,0 +1,7 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        /* TODO: a_1 a_1 */
+    }
+}
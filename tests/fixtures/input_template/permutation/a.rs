@@ -0,0 +1,8 @@
+use proconio::{input, marker::Usize1};
+fn main() {
+    input! {
+        n: usize,
+        p: [Usize1; n], // permutation
+        a: [usize; n],
+    }
+}
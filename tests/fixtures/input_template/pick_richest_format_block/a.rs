@@ -0,0 +1,8 @@
+use proconio::input;
+// NOTE: alternative input format: N
+fn main() {
+    input! {
+        n: usize,
+        a: [usize; n],
+    }
+}
@@ -0,0 +1,12 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+    }
+    print_array::<usize>(todo!()); // TODO: the answer
+}
+
+fn print_array<T: std::fmt::Display>(a: &[T]) {
+    let a = a.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+    println!("{}", a.join(" "));
+}
@@ -0,0 +1,7 @@
+// proconio = "=0.4.5"
+use proconio::{input, marker::Chars};
+fn main() {
+    input! {
+        s: Chars,
+    }
+}
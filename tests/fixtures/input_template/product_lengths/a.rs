@@ -0,0 +1,9 @@
+use proconio::input;
+fn main() {
+    input! {
+        h: usize,
+        w: usize,
+        a: [usize; h*w],
+        b: [usize; h*w],
+    }
+}
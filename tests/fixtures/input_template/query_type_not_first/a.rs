@@ -0,0 +1,15 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        q: usize,
+    }
+    for _ in 0..q {
+        input! { u: usize, v: usize, qt: usize }
+        match qt {
+            1 => {},
+            _ => {}, // 2
+        }
+        /* TODO: process query */
+    }
+}
@@ -0,0 +1,15 @@
+use proconio::input;
+fn main() {
+    input! {
+        q: usize,
+    }
+    for _ in 0..q {
+        input! { qt: usize }
+        match qt {
+            0 => { input! { x: usize } },
+            1 => { input! { x: usize, y: usize } },
+            _ => {}, // 2
+        }
+        /* TODO: process query */
+    }
+}
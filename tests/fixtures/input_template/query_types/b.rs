@@ -0,0 +1,16 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        q: usize,
+    }
+    for _ in 0..q {
+        input! { qt: usize }
+        match qt {
+            1 => { input! { x: usize, y: usize } },
+            2 => { input! { k: usize, a: [usize; k] } },
+            _ => { input! { k: usize, b: [usize; k], c: usize } }, // 3
+        }
+        /* TODO: process query */
+    }
+}
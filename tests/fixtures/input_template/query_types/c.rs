@@ -0,0 +1,15 @@
+use proconio::input;
+fn main() {
+    input! {
+        q: usize,
+    }
+    for _ in 0..q {
+        input! { qt: usize }
+        match qt {
+            1 => { input! { x: usize } },
+            3 => { input! { x: usize } },
+            _ => unreachable!(),
+        }
+        /* TODO: process query */
+    }
+}
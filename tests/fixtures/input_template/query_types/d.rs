@@ -0,0 +1,15 @@
+use proconio::input;
+fn main() {
+    input! {
+        q: usize,
+    }
+    for _ in 0..q {
+        input! { qt: String }
+        match &*qt {
+            "add" => { input! { x: usize } },
+            "get" => {},
+            _ => unreachable!(),
+        }
+        /* TODO: process query */
+    }
+}
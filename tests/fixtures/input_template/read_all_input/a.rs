@@ -0,0 +1,19 @@
+use proconio::{input, source::once::OnceSource};
+use std::io::Read as _;
+fn main() {
+    let mut buf = String::new();
+    std::io::stdin().read_to_string(&mut buf).unwrap();
+    let mut source = OnceSource::from(buf.as_str());
+    input! {
+        from &mut source,
+        t: usize,
+    }
+    for _ in 0..t {
+        input! {
+            from &mut source,
+            n: usize,
+            a: [usize; n],
+        }
+        /* TODO: solve testcase */
+    }
+}
@@ -0,0 +1,10 @@
+use proconio::input;
+fn main() {
+    input! {
+        h: usize,
+        w: usize,
+        n: usize,
+        k: usize,
+        a: [[usize; k]; n],
+    }
+}
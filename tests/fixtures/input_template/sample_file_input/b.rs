@@ -0,0 +1,13 @@
+use proconio::{input, source::once::OnceSource};
+use std::io::{stdin, BufReader};
+fn main() {
+    #[cfg(debug_assertions)]
+    let mut source = OnceSource::new(BufReader::new(std::fs::File::open(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/b/1.in")).unwrap()));
+    #[cfg(not(debug_assertions))]
+    let mut source = OnceSource::new(BufReader::new(stdin()));
+    input! {
+        from &mut source,
+        n: usize,
+        a: [usize; n],
+    }
+}
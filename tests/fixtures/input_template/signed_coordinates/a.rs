@@ -0,0 +1,7 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        xy: [(i64, i64); n], // NOTE: no sample to check the column types
+    }
+}
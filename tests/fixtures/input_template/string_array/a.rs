@@ -0,0 +1,7 @@
+use proconio::{input, marker::Chars};
+fn main() {
+    input! {
+        n: usize,
+        s: [Chars; n],
+    }
+}
@@ -0,0 +1,8 @@
+use proconio::{input, marker::Chars};
+fn main() {
+    input! {
+        n: usize,
+        s: Chars, // |s| = n
+        t: Chars, // |t| <= 2*10^5
+    }
+}
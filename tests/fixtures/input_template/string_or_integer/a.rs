@@ -0,0 +1,9 @@
+use proconio::{input, marker::Chars};
+fn main() {
+    input! {
+        s: usize,
+        x: Chars,
+        n: usize,
+        k: Chars, // |k| = n
+    }
+}
@@ -0,0 +1,7 @@
+use proconio::{input, marker::Chars};
+fn main() {
+    input! {
+        s: Chars,
+        t: Chars,
+    }
+}
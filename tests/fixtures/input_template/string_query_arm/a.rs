@@ -0,0 +1,14 @@
+use proconio::{input, marker::Chars};
+fn main() {
+    input! {
+        q: usize,
+    }
+    for _ in 0..q {
+        input! { qt: usize }
+        match qt {
+            1 => { input! { s: Chars } },
+            _ => { input! { k: usize } }, // 2
+        }
+        /* TODO: process query */
+    }
+}
@@ -0,0 +1,8 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        a1: [usize; n],
+        a2: [usize; n],
+    }
+}
@@ -0,0 +1,8 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        a: [usize; n],
+    }
+    let _ = (&n, &a);
+}
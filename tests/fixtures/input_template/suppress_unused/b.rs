@@ -0,0 +1,13 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+    }
+    let mut a = Vec::with_capacity(n);
+    for _ in 0..n {
+        input! { k: usize, row: [usize; k] }
+        let _ = (&k, &row);
+        a.push(row);
+    }
+    let _ = (&n, &a);
+}
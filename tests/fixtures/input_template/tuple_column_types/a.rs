@@ -0,0 +1,8 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        m: usize,
+        uvc: [(usize, usize, char); m],
+    }
+}
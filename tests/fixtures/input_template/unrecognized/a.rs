@@ -0,0 +1,8 @@
+use proconio::input;
+fn main() {
+    // TODO: parse manually. The input format is:
+    //   \{ a \mid b \}
+    //   \{ c \mid d \}
+    input! {
+    }
+}
@@ -0,0 +1,8 @@
+use std::io::Read as _;
+fn main() {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input).unwrap();
+    // TODO: parse manually. The input format is:
+    //   \{ a \mid b \}
+    //   \{ c \mid d \}
+}
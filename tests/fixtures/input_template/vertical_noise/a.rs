@@ -0,0 +1,8 @@
+use proconio::{input, marker::Chars};
+fn main() {
+    input! {
+        n: usize,
+        b: [usize; n],
+        s: [Chars; n],
+    }
+}
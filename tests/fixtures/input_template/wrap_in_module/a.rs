@@ -0,0 +1,9 @@
+mod a {
+    use proconio::input;
+    pub fn main() {
+        input! {
+            n: usize,
+            a: [usize; n],
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+    }
+    yn(todo!()); // TODO: decide the answer
+}
+
+fn yn(b: bool) {
+    println!("{}", if b { "Yes" } else { "No" });
+}
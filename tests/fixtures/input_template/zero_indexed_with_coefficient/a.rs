@@ -0,0 +1,9 @@
+use proconio::input;
+fn main() {
+    input! {
+        n: usize,
+        a: [usize; 2*n],
+        b: [usize; n],
+        c: [usize; (2*n)+1],
+    }
+}
@@ -0,0 +1,103 @@
+use camino::Utf8Path;
+use cargo_compete::Reporter;
+use pretty_assertions::assert_eq;
+use std::{fmt, fs, io, path::Path};
+
+/// Each directory in `tests/fixtures/input_template` has a saved `task.html`, the expected
+/// `<letter>.rs` of every task in it, and optionally `options.toml` as the `input-template` table.
+#[test]
+fn fixtures() -> anyhow::Result<()> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("input_template");
+    for entry in fs::read_dir(root)? {
+        let dir = entry?.path();
+        let html = fs::read_to_string(dir.join("task.html"))?;
+        let options = fs::read_to_string(dir.join("options.toml")).unwrap_or_default();
+        let generated = cargo_compete::generate_template_from_html(&html, &options)?;
+        assert!(!generated.is_empty(), "{}: no task", dir.display());
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension() == Some("rs".as_ref()) {
+                let stem = path.file_stem().unwrap().to_string_lossy();
+                assert!(
+                    generated.keys().any(|l| l.to_lowercase() == stem),
+                    "{}: not generated",
+                    path.display(),
+                );
+            }
+        }
+        for (letter, content) in generated {
+            let expected_path = dir.join(format!("{}.rs", letter.to_lowercase()));
+            let expected = fs::read_to_string(&expected_path)?;
+            assert_eq!(expected.trim_end(), content, "{}", expected_path.display(),);
+        }
+    }
+    Ok(())
+}
+
+/// Keeps the warnings to look at.
+#[derive(Default)]
+struct Warnings(Vec<String>);
+
+impl Reporter for Warnings {
+    fn report_status(&mut self, _: &dyn fmt::Display, _: &dyn fmt::Display) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn report_warning(&mut self, message: &dyn fmt::Display) -> io::Result<()> {
+        self.0.push(message.to_string());
+        Ok(())
+    }
+}
+
+#[test]
+fn generate_templates() -> anyhow::Result<()> {
+    let tempdir = tempfile::Builder::new()
+        .prefix("cargo-compete-input-template-")
+        .tempdir()?;
+    let dest_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+    let mut warnings = Warnings::default();
+    assert!(cargo_compete::generate_templates(dest_dir, "", None, &mut warnings)?.is_empty());
+
+    fs::write(
+        dest_dir.join("task.html"),
+        r#"<span class="h2">B - Second</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>
+<span class="h2">A - First</span>
+<h3>入力</h3><pre>S</pre><h3>出力</h3>"#,
+    )?;
+    let generated = cargo_compete::generate_templates(dest_dir, "", None, &mut warnings)?;
+    let src_dir = dest_dir.join("src").join("bin");
+    assert_eq!(
+        vec![src_dir.join("b.rs"), src_dir.join("a.rs")],
+        generated
+            .iter()
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>(),
+    );
+    assert!(generated[1].1.contains("s: Chars,"));
+    assert!(warnings.0.is_empty(), "{:?}", warnings.0);
+
+    let name_fn = |letter: &str, title: &str| format!("{letter}-{title}.rs").to_lowercase().into();
+    let generated = cargo_compete::generate_templates(dest_dir, "", Some(&name_fn), &mut warnings)?;
+    assert_eq!(
+        vec![dest_dir.join("b-second.rs"), dest_dir.join("a-first.rs")],
+        generated
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>(),
+    );
+    Ok(())
+}
+
+#[test]
+fn duplicate_letters() {
+    let html = r#"<span class="h2">A - First</span>
+<h3>入力</h3><pre>N</pre><h3>出力</h3>
+<span class="h2">A - Again</span>
+<h3>入力</h3><pre>S</pre><h3>出力</h3>"#;
+    let err = cargo_compete::generate_template_from_html(html, "").unwrap_err();
+    assert_eq!("more than one task is lettered A", err.to_string());
+}